@@ -0,0 +1,130 @@
+use crate::chonk::Chonk;
+use crate::error::ChonkError;
+use soroban_sdk::{Bytes, Env};
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<'a> Chonk<'a> {
+    /// Standard, padded base64 encoding of the assembled content.
+    ///
+    /// Chunks are encoded as they are read, so only the output is held in
+    /// memory alongside one chunk.
+    pub fn assemble_base64(&self) -> Bytes {
+        let mut encoder = Encoder::new(self.env);
+        for chunk in self.iter() {
+            for byte in chunk.iter() {
+                encoder.push(byte);
+            }
+        }
+        encoder.finish()
+    }
+
+    /// Decode standard, padded base64 and store the result like
+    /// `write_chunked`, replacing the existing content.
+    ///
+    /// Fails with `InvalidChunkSize` if `chunk_size` is zero, with
+    /// `InvalidFormat` if `b64` isn't valid base64, or like
+    /// `write_slice_chunked` otherwise, leaving the collection untouched.
+    pub fn import_base64(&self, b64: Bytes, chunk_size: u32) -> Result<(), ChonkError> {
+        if chunk_size == 0 {
+            return Err(ChonkError::InvalidChunkSize);
+        }
+        let content = decode(self.env, &b64)?;
+        self.rewrite(content, chunk_size).map(|_| ())
+    }
+}
+
+/// Streaming encoder turning each group of three bytes into four characters
+struct Encoder {
+    out: Bytes,
+    group: [u8; 3],
+    len: usize,
+}
+
+impl Encoder {
+    fn new(env: &Env) -> Self {
+        Self {
+            out: Bytes::new(env),
+            group: [0; 3],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.group[self.len] = byte;
+        self.len += 1;
+        if self.len == 3 {
+            self.flush();
+        }
+    }
+
+    /// Encode the pending group, padding a partial one with `=`
+    fn flush(&mut self) {
+        let [a, b, c] = self.group;
+        let mut quad = [
+            ALPHABET[(a >> 2) as usize],
+            ALPHABET[((a & 0x03) << 4 | b >> 4) as usize],
+            ALPHABET[((b & 0x0f) << 2 | c >> 6) as usize],
+            ALPHABET[(c & 0x3f) as usize],
+        ];
+        for padded in quad.iter_mut().skip(self.len + 1) {
+            *padded = b'=';
+        }
+        self.out.extend_from_slice(&quad);
+        self.group = [0; 3];
+        self.len = 0;
+    }
+
+    fn finish(mut self) -> Bytes {
+        if self.len > 0 {
+            self.flush();
+        }
+        self.out
+    }
+}
+
+/// Decode standard, padded base64
+fn decode(env: &Env, b64: &Bytes) -> Result<Bytes, ChonkError> {
+    if !b64.len().is_multiple_of(4) {
+        return Err(ChonkError::InvalidFormat);
+    }
+
+    let mut out = Bytes::new(env);
+    let mut quad = [0u8; 4];
+    let quads = b64.len() / 4;
+    for q in 0..quads {
+        b64.slice(q * 4..q * 4 + 4).copy_into_slice(&mut quad);
+
+        // `=` may only pad the end of the final quad
+        let padding = match quad {
+            [_, _, b'=', b'='] => 2,
+            [_, _, _, b'='] => 1,
+            _ => 0,
+        };
+        if padding > 0 && q + 1 != quads {
+            return Err(ChonkError::InvalidFormat);
+        }
+
+        let mut bits = 0u32;
+        for &c in &quad[..4 - padding] {
+            bits = bits << 6 | sextet(c).ok_or(ChonkError::InvalidFormat)? as u32;
+        }
+        bits <<= 6 * padding as u32;
+
+        let bytes = bits.to_be_bytes();
+        out.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Ok(out)
+}
+
+/// Value of a base64 character
+fn sextet(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
@@ -0,0 +1,39 @@
+use crate::chonk::Chonk;
+use soroban_sdk::{Bytes, Env, Symbol};
+
+/// A single large value stored as a chunk collection
+///
+/// For storing one blob under an id and reading it back whole, without the
+/// chunk-level API. Convert a configured [`Chonk`] (for example one from
+/// [`Chonk::builder`]) to keep the blob in another storage kind.
+pub struct ChonkBlob<'a> {
+    chonk: Chonk<'a>,
+}
+
+impl<'a> ChonkBlob<'a> {
+    /// Open the blob stored under `id`
+    pub fn open(env: &'a Env, id: Symbol) -> Self {
+        Self {
+            chonk: Chonk::open(env, id),
+        }
+    }
+
+    /// Store `content`, replacing the previous value, split into chunks of
+    /// at most `chunk_size` bytes
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn set(&self, content: Bytes, chunk_size: u32) {
+        self.chonk.write_chunked(content, chunk_size);
+    }
+
+    /// The stored value (empty if never set)
+    pub fn get(&self) -> Bytes {
+        self.chonk.assemble()
+    }
+}
+
+impl<'a> From<Chonk<'a>> for ChonkBlob<'a> {
+    fn from(chonk: Chonk<'a>) -> Self {
+        Self { chonk }
+    }
+}
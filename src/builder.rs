@@ -0,0 +1,106 @@
+use crate::chonk::Chonk;
+use crate::storage::StorageKind;
+use soroban_sdk::{Address, Env, Symbol};
+
+/// Configures a [`Chonk`] handle before opening it
+///
+/// Created with [`Chonk::builder`]. The `chunk_size`, `append_only`,
+/// `with_events`, `journaled`, `hashed` and `owner` settings are recorded in the
+/// collection's metadata on its next write, so they keep applying to
+/// handles opened later without them; once recorded, passing `false` does
+/// not clear a flag and the stored chunk size and owner win over different
+/// ones. The storage kind, limits and TTL policy apply to this handle only.
+pub struct ChonkBuilder<'a> {
+    chonk: Chonk<'a>,
+}
+
+impl<'a> ChonkBuilder<'a> {
+    pub(crate) fn new(env: &'a Env, id: Symbol) -> Self {
+        Self {
+            chonk: Chonk::open(env, id),
+        }
+    }
+
+    /// Keep the collection's entries in `kind` storage (persistent by default)
+    pub fn storage(mut self, kind: StorageKind) -> Self {
+        self.chonk.storage_kind = kind;
+        self
+    }
+
+    /// Keep the collection's entries in temporary storage
+    pub fn temporary(self) -> Self {
+        self.storage(StorageKind::Temporary)
+    }
+
+    /// Limit the size of a single chunk accepted by `push`, `set` and
+    /// `insert`; longer chunks fail with `ChunkTooLarge`
+    pub fn max_chunk_size(mut self, max_chunk_size: u32) -> Self {
+        self.chonk.max_chunk_size = Some(max_chunk_size);
+        self
+    }
+
+    /// Cap the total bytes the collection may hold when writing via `try_push`
+    pub fn max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.chonk.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    /// Extend the TTL of every entry this handle writes to `extend_to`
+    /// ledgers whenever it is below `threshold`
+    pub fn ttl(mut self, threshold: u32, extend_to: u32) -> Self {
+        self.chonk.ttl = Some((threshold, extend_to));
+        self
+    }
+
+    /// Require `owner`'s authorization for every write to the collection.
+    /// Only applies if the collection is created through this handle; an
+    /// existing collection keeps the owner it was created with, or none.
+    pub fn owner(mut self, owner: Address) -> Self {
+        self.chonk.owner = Some(owner);
+        self
+    }
+
+    /// Record `chunk_size` as the collection's chunk size; `append` with any
+    /// other `max_chunk_size` then fails with `InvalidChunkSize`
+    pub fn chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chonk.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Only allow chunks to be added at the end; changing or removing
+    /// existing chunks fails with `AppendOnly`
+    pub fn append_only(mut self, append_only: bool) -> Self {
+        self.chonk.append_only = append_only;
+        self
+    }
+
+    /// Publish a `(chonk, action, id)` event for every mutation
+    pub fn with_events(mut self, events: bool) -> Self {
+        self.chonk.events = events;
+        self
+    }
+
+    /// Record every mutation in a journal read by `changes_since`
+    pub fn journaled(mut self, journal: bool) -> Self {
+        self.chonk.journal = journal;
+        self
+    }
+
+    /// Keep a digest of the content in metadata, updated on every write, so
+    /// `content_hash` doesn't read the chunks. `push` folds the new chunk
+    /// into the digest; every other write rereads all chunks to rehash.
+    pub fn hashed(mut self, hashed: bool) -> Self {
+        self.chonk.hashed = hashed;
+        self
+    }
+
+    /// Open the configured collection
+    pub fn build(self) -> Chonk<'a> {
+        self.chonk
+    }
+
+    /// Open the configured collection. Same as `build`.
+    pub fn open(self) -> Chonk<'a> {
+        self.build()
+    }
+}
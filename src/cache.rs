@@ -0,0 +1,53 @@
+use crate::storage::StorageKind;
+use crate::types::ChonkMeta;
+use core::cell::RefCell;
+use soroban_sdk::Symbol;
+
+/// Metadata cache shared by handles opened with [`Chonk::open_cached`].
+///
+/// A cache lives for a single contract invocation and holds the metadata
+/// of one collection, tagged with its id and storage kind. Handles sharing
+/// it for that collection see each other's writes without re-reading the
+/// metadata entry; a handle for any other collection misses and reads
+/// storage, so sharing a cache across ids is safe but saves nothing.
+/// Writes made through handles that don't share the cache (including
+/// `Chonk::swap_collections`) are not observed.
+///
+/// [`Chonk::open_cached`]: crate::Chonk::open_cached
+#[derive(Default)]
+pub struct ChonkCache {
+    entry: RefCell<Option<(Symbol, StorageKind, ChonkMeta)>>,
+}
+
+impl ChonkCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached metadata of collection `id` in `kind` storage, if that is the
+    /// collection the cache holds
+    pub(crate) fn get(&self, id: &Symbol, kind: StorageKind) -> Option<ChonkMeta> {
+        match &*self.entry.borrow() {
+            Some((cached_id, cached_kind, meta)) if cached_id == id && *cached_kind == kind => {
+                Some(meta.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Replace the cached metadata with that of collection `id` in `kind`
+    /// storage
+    pub(crate) fn set(&self, id: &Symbol, kind: StorageKind, meta: ChonkMeta) {
+        *self.entry.borrow_mut() = Some((id.clone(), kind, meta));
+    }
+
+    /// Drop the cached metadata if it is that of collection `id` in `kind`
+    /// storage
+    pub(crate) fn invalidate(&self, id: &Symbol, kind: StorageKind) {
+        let mut entry = self.entry.borrow_mut();
+        if matches!(&*entry, Some((cached_id, cached_kind, _)) if cached_id == id && *cached_kind == kind)
+        {
+            *entry = None;
+        }
+    }
+}
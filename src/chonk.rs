@@ -1,17 +1,194 @@
+use crate::builder::ChonkBuilder;
+use crate::cache::ChonkCache;
+use crate::codec::ChonkCodec;
+use crate::error::ChonkError;
 use crate::iter::ChonkIter;
-use crate::types::{ChonkKey, ChonkMeta};
-use soroban_sdk::{Bytes, Env, Symbol, Vec};
+use crate::slice::ChonkSlice;
+use crate::storage::{StorageKind, Store};
+use crate::types::{
+    ChonkKey, ChonkLayout, ChonkLinks, ChonkMeta, ChonkMetaV1, ChonkNode, ChonkSlots,
+};
+use crate::view::ChonkView;
+use core::cmp::Ordering;
+use core::iter::Rev;
+use core::ops::Range;
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec, panic_with_error,
+    symbol_short,
+};
+
+/// Estimated bytes each chunk entry costs beyond its payload: the ledger
+/// key (contract address, durability and serialized `ChonkKey`) plus the
+/// framing around the stored value. Used by `overhead_bytes`.
+pub const ENTRY_OVERHEAD_BYTES: u32 = 96;
+
+/// Number of most recent versions a journaled collection keeps change
+/// records for. Each journaled write deletes the record that falls out of
+/// the window, so the journal, and the work `clear` does to remove it,
+/// stays bounded however long the collection lives.
+pub const JOURNAL_WINDOW: u64 = 64;
 
 /// A collection of chunked content stored in contract storage
+///
+/// Cloning a `Chonk` clones the handle, not the data: both handles address
+/// the same stored collection.
+#[derive(Clone)]
 pub struct Chonk<'a> {
-    env: &'a Env,
+    pub(crate) env: &'a Env,
     id: Symbol,
+    pub(crate) max_chunk_size: Option<u32>,
+    pub(crate) max_total_bytes: Option<u64>,
+    cache: Option<&'a ChonkCache>,
+    layout: LayoutKind,
+    pub(crate) storage_kind: StorageKind,
+    pub(crate) append_only: bool,
+    pub(crate) events: bool,
+    pub(crate) journal: bool,
+    pub(crate) chunk_size: Option<u32>,
+    pub(crate) owner: Option<Address>,
+    pub(crate) ttl: Option<(u32, u32)>,
+    pub(crate) hashed: bool,
+    codec: Option<&'a dyn ChonkCodec>,
+}
+
+/// Layout a handle gives the collections it creates
+#[derive(Clone, Copy, PartialEq)]
+enum LayoutKind {
+    Dense,
+    Gapped,
+    Linked,
 }
 
 impl<'a> Chonk<'a> {
     /// Create or open a chunk collection
     pub fn open(env: &'a Env, id: Symbol) -> Self {
-        Self { env, id }
+        Self {
+            env,
+            id,
+            max_chunk_size: None,
+            max_total_bytes: None,
+            cache: None,
+            layout: LayoutKind::Dense,
+            storage_kind: StorageKind::Persistent,
+            append_only: false,
+            events: false,
+            journal: false,
+            chunk_size: None,
+            owner: None,
+            ttl: None,
+            hashed: false,
+            codec: None,
+        }
+    }
+
+    /// Open a collection like `open`, failing with `InvalidArgument` if `id`
+    /// is empty. For ids built at runtime, where an empty symbol would
+    /// silently share keys with every other empty-id collection. Symbols
+    /// can't exceed 32 characters, so no length check is needed.
+    pub fn try_open(env: &'a Env, id: Symbol) -> Result<Self, ChonkError> {
+        if id == symbol_short!("") {
+            return Err(ChonkError::InvalidArgument);
+        }
+        Ok(Self::open(env, id))
+    }
+
+    /// Configure storage kind, limits and flags before opening a collection
+    pub fn builder(env: &'a Env, id: Symbol) -> ChonkBuilder<'a> {
+        ChonkBuilder::new(env, id)
+    }
+
+    /// Open a collection that, if created through this handle, uses the
+    /// gapped layout: chunks live under sparse slot keys and a
+    /// logical-to-physical index map is kept in the metadata.
+    ///
+    /// Inserting or removing in the middle then writes only the affected
+    /// chunk and the metadata instead of shifting every later chunk, at the
+    /// cost of a larger metadata entry. An existing collection keeps the
+    /// layout it was created with, whichever constructor opens it.
+    pub fn open_gapped(env: &'a Env, id: Symbol) -> Self {
+        Self {
+            layout: LayoutKind::Gapped,
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Open a collection that, if created through this handle, uses the
+    /// linked layout: each chunk is a node holding its data and the ids of
+    /// its neighbours, and the metadata holds the head and tail.
+    ///
+    /// Inserting or removing writes only the new or removed nodes, their
+    /// two neighbours and the metadata. Finding a chunk by index walks the
+    /// list from the nearer end, so `get`, `set` and positional inserts cost
+    /// O(n) reads; `iter` follows the links and stays O(1) per chunk.
+    pub fn open_linked(env: &'a Env, id: Symbol) -> Self {
+        Self {
+            layout: LayoutKind::Linked,
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Open a collection that records every mutation in a journal, so
+    /// off-chain replicas can fetch what changed with `changes_since`.
+    ///
+    /// Each write then stores one extra entry. Like the builder flags,
+    /// journaling is recorded in the metadata on the next write and keeps
+    /// applying to handles opened later without it.
+    pub fn open_journaled(env: &'a Env, id: Symbol) -> Self {
+        Self {
+            journal: true,
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Open a collection kept in `kind` storage, for callers that choose the
+    /// storage at runtime. Same as `builder(env, id).storage(kind).build()`.
+    pub fn open_with(env: &'a Env, id: Symbol, kind: StorageKind) -> Self {
+        Self {
+            storage_kind: kind,
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Open a collection whose chunks pass through `codec`: encoded when
+    /// written, decoded when read. Lengths, `total_bytes`, digests and
+    /// checksums all describe the decoded bytes.
+    ///
+    /// Every handle on the collection must use the same codec; one opened
+    /// without it reads the encoded bytes.
+    pub fn open_with_codec(env: &'a Env, id: Symbol, codec: &'a dyn ChonkCodec) -> Self {
+        Self {
+            codec: Some(codec),
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Open a collection whose metadata is cached in `cache`, shared with
+    /// every other handle opened on the same cache for this id. A cache
+    /// holding another collection's metadata is a miss, never a stale hit.
+    ///
+    /// The cache is only valid for the current invocation: handles that
+    /// share it see each other's writes without redundant storage reads.
+    pub fn open_cached(env: &'a Env, id: Symbol, cache: &'a ChonkCache) -> Self {
+        Self {
+            cache: Some(cache),
+            ..Self::open(env, id)
+        }
+    }
+
+    /// Limit the size of each chunk this handle writes, measured as stored
+    /// (after the codec, if any). A longer chunk fails with `ChunkTooLarge`,
+    /// or panics with it where the method doesn't return a `Result`, before
+    /// anything is written. Chunks already stored are not checked.
+    pub fn with_max_chunk_size(mut self, max_chunk_size: u32) -> Self {
+        self.max_chunk_size = Some(max_chunk_size);
+        self
+    }
+
+    /// Cap the total bytes the collection may hold when writing via `try_push`
+    pub fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
     }
 
     /// Get the collection ID
@@ -19,14 +196,92 @@ impl<'a> Chonk<'a> {
         &self.id
     }
 
+    /// Get the environment the handle was opened with, for code building on
+    /// a `Chonk` without passing the `Env` separately
+    pub fn env(&self) -> &'a Env {
+        self.env
+    }
+
+    /// Get the storage the collection's entries live in, for code reading
+    /// them directly with `chunk_key` and `meta_key`
+    pub fn storage_kind(&self) -> StorageKind {
+        self.storage_kind
+    }
+
+    /// Storage key of chunk `index`, resolved through the collection's
+    /// layout, or `None` if `index >= count`. Dense collections store it
+    /// under `Chunk`, gapped ones under the `Slot` the index maps to, and
+    /// linked ones in a `ChonkNode` under `Node`, found by walking the list.
+    ///
+    /// A collection with a codec stores the encoded bytes under the key.
+    pub fn chunk_key(&self, index: u32) -> Option<ChonkKey> {
+        let meta = self.meta();
+        if index >= meta.count {
+            return None;
+        }
+        Some(match &meta.layout {
+            ChonkLayout::Dense => ChonkKey::Chunk(self.id.clone(), index),
+            ChonkLayout::Gapped(slots) => {
+                ChonkKey::Slot(self.id.clone(), slots.map.get_unchecked(index))
+            }
+            ChonkLayout::Linked(links) => {
+                ChonkKey::Node(self.id.clone(), self.node_at(links, meta.count, index))
+            }
+        })
+    }
+
+    /// Read-only view of the same collection, for code that must not write
+    pub fn view(&self) -> ChonkView<'a> {
+        ChonkView::new(self.clone())
+    }
+
+    /// Lazy view of the `len` logical bytes starting at `start`; nothing is
+    /// read until its content is requested
+    pub fn slice_view(&self, start: u32, len: u32) -> ChonkSlice<'_> {
+        ChonkSlice::new(self, start, len)
+    }
+
     /// Get metadata for this collection
     pub fn meta(&self) -> ChonkMeta {
+        if let Some(meta) = self
+            .cache
+            .and_then(|cache| cache.get(&self.id, self.storage_kind))
+        {
+            return meta;
+        }
+
         let key = ChonkKey::Meta(self.id.clone());
-        self.env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_default()
+        let meta = match load_meta(&self.storage(), &key) {
+            Some(mut meta) => {
+                // Flags requested by this handle stick once written. The
+                // owner is only set on creation, so a handle can't claim an
+                // existing collection.
+                meta.append_only |= self.append_only;
+                meta.events |= self.events;
+                meta.journal |= self.journal;
+                meta.hashed |= self.hashed;
+                meta.chunk_size = meta.chunk_size.or(self.chunk_size);
+                meta
+            }
+            None => self.empty_meta(),
+        };
+        if let Some(cache) = self.cache {
+            cache.set(&self.id, self.storage_kind, meta.clone());
+        }
+        meta
+    }
+
+    /// Get metadata, failing with `NotFound` if the collection has never
+    /// been written (or has been cleared)
+    pub fn meta_checked(&self) -> Result<ChonkMeta, ChonkError> {
+        let key = ChonkKey::Meta(self.id.clone());
+        load_meta(&self.storage(), &key).ok_or(ChonkError::NotFound)
+    }
+
+    /// Check whether the collection has stored metadata
+    pub fn exists(&self) -> bool {
+        let key = ChonkKey::Meta(self.id.clone());
+        self.storage().has(&key)
     }
 
     /// Get number of chunks
@@ -34,220 +289,2415 @@ impl<'a> Chonk<'a> {
         self.meta().count
     }
 
+    /// Get number of chunks by reading only the dedicated count entry,
+    /// without deserializing the full metadata. Falls back to `count()` for
+    /// collections last written before the count entry existed.
+    pub fn count_fast(&self) -> u32 {
+        let key = ChonkKey::Count(self.id.clone());
+        self.storage().get(&key).unwrap_or_else(|| self.count())
+    }
+
     /// Get total bytes across all chunks
-    pub fn total_bytes(&self) -> u32 {
+    pub fn total_bytes(&self) -> u64 {
         self.meta().total_bytes
     }
 
+    /// Get `(count, total_bytes)` from a single metadata read
+    pub fn stats(&self) -> (u32, u64) {
+        let meta = self.meta();
+        (meta.count, meta.total_bytes)
+    }
+
+    /// Estimated bytes stored beyond the payload counted by `total_bytes`:
+    /// `ENTRY_OVERHEAD_BYTES` per chunk plus the serialized metadata.
+    ///
+    /// Larger chunks mean fewer entries and less overhead, up to the
+    /// per-entry size limit.
+    pub fn overhead_bytes(&self) -> u32 {
+        let meta = self.meta();
+        meta.count
+            .saturating_mul(ENTRY_OVERHEAD_BYTES)
+            .saturating_add(meta.to_xdr(self.env).len())
+    }
+
     /// Check if the collection is empty
     pub fn is_empty(&self) -> bool {
         self.count() == 0
     }
 
+    /// Digest of the content, chaining each chunk into a SHA-256 hash in
+    /// order. Collections with the same chunks have the same digest.
+    ///
+    /// Hashed collections (see `ChonkBuilder::hashed`) keep the digest in
+    /// their metadata, updated by every write through this crate, so reading
+    /// it costs one metadata read. Otherwise it is computed from the chunks.
+    /// A digest kept for an older version, e.g. after the chunks were
+    /// written behind the crate's back, is ignored and recomputed.
+    pub fn content_hash(&self) -> BytesN<32> {
+        let meta = self.meta();
+        match &meta.content_hash {
+            Some(digest) if hash_is_current(&meta) => digest.clone(),
+            _ => {
+                let count = meta.count;
+                self.digest(ChonkIter::over(self.clone(), meta, count))
+            }
+        }
+    }
+
+    /// Compare content by `content_hash`, which for two hashed collections
+    /// reads only their metadata
+    pub fn content_eq_hash(&self, other: &Chonk) -> bool {
+        self.content_hash() == other.content_hash()
+    }
+
+    /// `(version, index)` for each write after `version` to a journaled
+    /// collection, oldest first, where `index` is the first chunk the write
+    /// affected. Empty if the collection isn't journaled.
+    ///
+    /// Only the last `JOURNAL_WINDOW` versions are kept, so a caller more
+    /// than that far behind `meta().version` must resync from the content.
+    /// Reads one journal entry per retained version. Clearing the collection
+    /// also clears its journal, and its version restarts from zero.
+    pub fn changes_since(&self, version: u64) -> Vec<(u64, u32)> {
+        let meta = self.meta();
+        let mut changes = Vec::new(self.env);
+        let retained = journal_versions(&meta);
+        let first = core::cmp::max(version.saturating_add(1), retained.start);
+        for v in first..retained.end {
+            let key = ChonkKey::Change(self.id.clone(), v);
+            if let Some(index) = self.storage().get::<u32>(&key) {
+                changes.push_back((v, index));
+            }
+        }
+        changes
+    }
+
+    /// The collection's content type tag, if one has been set
+    pub fn content_type(&self) -> Option<Bytes> {
+        self.meta().content_type
+    }
+
+    /// Tag the collection with a content type (e.g. `b"image/png"`) for
+    /// consumers of its content.
+    ///
+    /// The tag is informational: it doesn't change the chunks or the
+    /// version, and it is allowed on append-only collections. It is kept by
+    /// rewrites such as `write_chunked` and dropped by `clear`.
+    pub fn set_content_type(&self, content_type: Bytes) {
+        let mut meta = self.meta();
+        meta.content_type = Some(content_type);
+        self.save_meta(&meta);
+    }
+
+    /// Index the next `push` will assign, i.e. the current count
+    pub fn next_index(&self) -> u32 {
+        self.count()
+    }
+
+    /// Check whether `index` addresses an existing chunk
+    pub fn contains_index(&self, index: u32) -> bool {
+        index < self.count()
+    }
+
+    /// Inclusive range of valid chunk indices, `(0, count - 1)`, or `None`
+    /// if the collection is empty
+    pub fn valid_range(&self) -> Option<(u32, u32)> {
+        self.count().checked_sub(1).map(|last| (0, last))
+    }
+
+    /// Estimate how many more chunks of `chunk_size` bytes `try_push` would
+    /// accept.
+    ///
+    /// Only the limits this crate knows about are considered: the handle's
+    /// `max_chunk_size` and `max_total_bytes`, and the `u32` chunk count.
+    /// Soroban's own limits (entry size, per-transaction write bytes, fees)
+    /// are network settings not visible to the contract, so treat the result
+    /// as an upper bound.
+    pub fn estimate_capacity(&self, chunk_size: u32) -> u32 {
+        if self.max_chunk_size.is_some_and(|max| chunk_size > max) {
+            return 0;
+        }
+
+        let meta = self.meta();
+        let by_count = u32::MAX - meta.count;
+        let by_bytes = match self.max_total_bytes {
+            Some(cap) if chunk_size > 0 => cap.saturating_sub(meta.total_bytes) / chunk_size as u64,
+            _ => u64::MAX,
+        };
+        core::cmp::min(by_count as u64, by_bytes) as u32
+    }
+
     // ─── Read Operations ───────────────────────────────────
 
     /// Get a single chunk by index
+    ///
+    /// On a dense handle this is a single storage read; gapped collections
+    /// read the metadata first to find the chunk's slot.
     pub fn get(&self, index: u32) -> Option<Bytes> {
-        let key = ChonkKey::Chunk(self.id.clone(), index);
-        self.env.storage().persistent().get(&key)
+        if self.layout == LayoutKind::Dense {
+            let key = ChonkKey::Chunk(self.id.clone(), index);
+            if let Some(chunk) = self.storage().get(&key) {
+                return Some(self.decode(chunk));
+            }
+
+            // A miss may also mean the collection was created gapped
+            let meta = self.meta();
+            if meta.layout == ChonkLayout::Dense {
+                return None;
+            }
+            return self.load_chunk(&meta, index);
+        }
+
+        self.load_chunk(&self.meta(), index)
     }
 
     /// Get multiple chunks as a Vec
     pub fn get_range(&self, start: u32, count: u32) -> Vec<Bytes> {
+        self.get_range_indices(start, start.saturating_add(count))
+    }
+
+    /// Concatenate `count` chunks starting at `start`, clamped like
+    /// `get_range`, returning the bytes and the number of chunks read
+    pub fn get_range_assembled(&self, start: u32, count: u32) -> (Bytes, u32) {
+        let mut result = Bytes::new(self.env);
+        let chunks = self.get_range(start, count);
+        for chunk in chunks.iter() {
+            result.append(&chunk);
+        }
+        (result, chunks.len())
+    }
+
+    /// Get chunks `[start, end)` as a Vec, with `end` clamped to `count`
+    pub fn get_range_indices(&self, start: u32, end: u32) -> Vec<Bytes> {
+        self.get_stepped(start, end, 1)
+    }
+
+    /// Get every `step`th chunk of `[start, end)`, starting at `start`, with
+    /// `end` clamped to `count`. Panics if `step` is zero.
+    pub fn get_stepped(&self, start: u32, end: u32, step: u32) -> Vec<Bytes> {
+        if step == 0 {
+            panic!("Step must be non-zero");
+        }
+
         let mut result = Vec::new(self.env);
         let meta = self.meta();
-        let end = core::cmp::min(start + count, meta.count);
+        let end = core::cmp::min(end, meta.count);
 
-        for i in start..end {
-            if let Some(chunk) = self.get(i) {
+        let mut i = start;
+        while i < end {
+            if let Some(chunk) = self.load_chunk(&meta, i) {
                 result.push_back(chunk);
             }
+            i = match i.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
         }
         result
     }
 
     /// Iterate over all chunks
     pub fn iter(&self) -> ChonkIter<'_> {
-        ChonkIter::new(self.env, self.id.clone(), self.count())
+        let meta = self.meta();
+        let count = meta.count;
+        ChonkIter::over(self.clone(), meta, count)
     }
 
-    /// Assemble all chunks into a single Bytes
-    /// Warning: May hit execution limits for very large content
-    pub fn assemble(&self) -> Bytes {
-        let mut result = Bytes::new(self.env);
+    /// Iterate over all chunks paired with their index
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (u32, Bytes)> + '_ {
+        self.iter()
+            .enumerate()
+            .map(|(index, chunk)| (index as u32, chunk))
+    }
+
+    /// Iterate over all chunks from the last down to chunk 0. Same as
+    /// `iter().rev()`.
+    pub fn iter_rev(&self) -> Rev<ChonkIter<'_>> {
+        self.iter().rev()
+    }
+
+    /// Collect every chunk, in order, reading the metadata once
+    pub fn to_vec(&self) -> Vec<Bytes> {
+        let mut result = Vec::new(self.env);
         for chunk in self.iter() {
-            result.append(&chunk);
+            result.push_back(chunk);
         }
         result
     }
 
-    // ─── Write Operations ──────────────────────────────────
+    /// Call `f` with the index and contents of each chunk in order, holding
+    /// only one chunk at a time. Suited to streaming content out (to a hash,
+    /// an event, a host function) without assembling it.
+    pub fn for_each_chunk<F: FnMut(u32, &Bytes)>(&self, mut f: F) {
+        for (index, chunk) in self.iter().enumerate() {
+            f(index as u32, &chunk);
+        }
+    }
 
-    /// Save metadata
-    fn save_meta(&self, meta: &ChonkMeta) {
-        let key = ChonkKey::Meta(self.id.clone());
-        self.env.storage().persistent().set(&key, meta);
+    /// Call `f` with the index and contents of each chunk in order, stopping
+    /// at the first error and returning it. Later chunks are not read.
+    pub fn try_for_each<E, F: FnMut(u32, Bytes) -> Result<(), E>>(
+        &self,
+        mut f: F,
+    ) -> Result<(), E> {
+        for (index, chunk) in self.iter().enumerate() {
+            f(index as u32, chunk)?;
+        }
+        Ok(())
     }
 
-    /// Append a chunk to the end, returns the new index
-    pub fn push(&self, data: Bytes) -> u32 {
-        let mut meta = self.meta();
-        let index = meta.count;
+    /// Iterate over the length of each chunk.
+    ///
+    /// Chunk lengths aren't indexed separately, so each chunk is loaded to
+    /// measure it; this costs the same storage reads as `iter`.
+    pub fn lengths(&self) -> impl Iterator<Item = u32> + '_ {
+        self.iter().map(|chunk| chunk.len())
+    }
 
-        let key = ChonkKey::Chunk(self.id.clone(), index);
-        let data_len = data.len();
-        self.env.storage().persistent().set(&key, &data);
+    /// Total length of chunks `[start, start + count)`, clamped to the
+    /// collection's chunks, without assembling them. Lets a caller work out
+    /// how many chunks fit in a read budget.
+    ///
+    /// Like `lengths`, this loads each chunk in the range to measure it.
+    pub fn chunk_range_bytes(&self, start: u32, count: u32) -> u64 {
+        let meta = self.meta();
+        let end = core::cmp::min(start.saturating_add(count), meta.count);
+        (start..end)
+            .filter_map(|index| self.load_chunk(&meta, index))
+            .map(|chunk| chunk.len() as u64)
+            .sum()
+    }
 
-        meta.count += 1;
-        meta.total_bytes += data_len;
-        meta.version += 1;
-        self.save_meta(&meta);
+    /// Length of every chunk in order, for a client that fetches the chunk
+    /// entries itself and needs to know how to slice the content.
+    ///
+    /// Like `lengths`, this loads every chunk to measure it.
+    pub fn manifest(&self) -> Vec<u32> {
+        let mut manifest = Vec::new(self.env);
+        for len in self.lengths() {
+            manifest.push_back(len);
+        }
+        manifest
+    }
 
-        index
+    /// CRC-32 (IEEE) of every chunk in order, so a client holding an
+    /// earlier list can tell exactly which chunks changed
+    pub fn checksum_per_chunk(&self) -> Vec<u32> {
+        let mut checksums = Vec::new(self.env);
+        for chunk in self.iter() {
+            checksums.push_back(crc32(&chunk));
+        }
+        checksums
     }
 
-    /// Replace a specific chunk
-    pub fn set(&self, index: u32, data: Bytes) {
-        let mut meta = self.meta();
-        if index >= meta.count {
-            panic!("Index out of bounds");
+    /// Check every chunk against `expected`, a list like the one
+    /// `checksum_per_chunk` returns, e.g. after a restore or migration.
+    ///
+    /// Fails with `IntegrityMismatch` at the first chunk whose CRC differs,
+    /// or if `expected` doesn't have one checksum per chunk. Chunks after
+    /// the first mismatch are not read.
+    pub fn verify_against(&self, expected: Vec<u32>) -> Result<(), ChonkError> {
+        if expected.len() != self.count() {
+            return Err(ChonkError::IntegrityMismatch);
         }
+        for (chunk, checksum) in self.iter().zip(expected.iter()) {
+            if crc32(&chunk) != checksum {
+                return Err(ChonkError::IntegrityMismatch);
+            }
+        }
+        Ok(())
+    }
 
-        let key = ChonkKey::Chunk(self.id.clone(), index);
+    /// Check that the stored entries agree with the metadata: every one of
+    /// `count` chunks exists, a dense collection has no chunk past the end,
+    /// the count entry matches, and the chunk lengths add up to
+    /// `total_bytes`. Fails with `Corrupted` on the first disagreement.
+    ///
+    /// Reads every chunk once; meant for tests and as a guard before
+    /// critical operations. `Corrupted` carries no index; `corrupted_index`
+    /// finds the first missing chunk.
+    pub fn assert_consistent(&self) -> Result<(), ChonkError> {
+        let meta = self.meta();
+        let count_key = ChonkKey::Count(self.id.clone());
+        if self
+            .storage()
+            .get::<u32>(&count_key)
+            .is_some_and(|count| count != meta.count)
+        {
+            return Err(ChonkError::Corrupted);
+        }
+        if matches!(meta.layout, ChonkLayout::Dense)
+            && self
+                .storage()
+                .has(&ChonkKey::Chunk(self.id.clone(), meta.count))
+        {
+            return Err(ChonkError::Corrupted);
+        }
 
-        // Adjust total_bytes
-        if let Some(old_data) = self.env.storage().persistent().get::<_, Bytes>(&key) {
-            meta.total_bytes -= old_data.len();
+        // The iterator stops early at a missing chunk
+        let (mut chunks, mut total_bytes) = (0u32, 0u64);
+        for chunk in self.iter() {
+            chunks += 1;
+            total_bytes += chunk.len() as u64;
+        }
+        if chunks != meta.count || total_bytes != meta.total_bytes {
+            return Err(ChonkError::Corrupted);
         }
-        meta.total_bytes += data.len();
-        meta.version += 1;
+        Ok(())
+    }
 
-        self.env.storage().persistent().set(&key, &data);
-        self.save_meta(&meta);
+    /// Index of the first of `count` chunks whose entry is missing, or
+    /// `None` if they all exist. Pinpoints the damage `assert_consistent`
+    /// reports as `Corrupted`.
+    pub fn corrupted_index(&self) -> Option<u32> {
+        let count = self.count();
+        let present = self.iter().count() as u32;
+        (present < count).then_some(present)
     }
 
-    /// Insert a chunk at index (shifts subsequent chunks)
-    pub fn insert(&self, index: u32, data: Bytes) {
-        let mut meta = self.meta();
-        if index > meta.count {
-            panic!("Index out of bounds");
+    /// Minimum, maximum and average (rounded down) chunk length, or all zero
+    /// for an empty collection. Useful when deciding whether to compact.
+    ///
+    /// Like `lengths`, this loads every chunk to measure it.
+    pub fn chunk_sizes_histogram(&self) -> (u32, u32, u32) {
+        let meta = self.meta();
+        if meta.count == 0 {
+            return (0, 0, 0);
         }
+        let (min, max) = self.lengths().fold((u32::MAX, 0), |(min, max), len| {
+            (min.min(len), max.max(len))
+        });
+        let avg = (meta.total_bytes / meta.count as u64) as u32;
+        (min, max, avg)
+    }
 
-        // Shift chunks from end to index
-        for i in (index..meta.count).rev() {
-            let from_key = ChonkKey::Chunk(self.id.clone(), i);
-            let to_key = ChonkKey::Chunk(self.id.clone(), i + 1);
-            if let Some(chunk) = self.env.storage().persistent().get::<_, Bytes>(&from_key) {
-                self.env.storage().persistent().set(&to_key, &chunk);
-            }
+    /// Iterate over the content in pages of `page_size` bytes, regardless of
+    /// how it is chunked; the last page may be shorter.
+    ///
+    /// Chunks are read as pages need them, so `pages(size).nth(n)` reads
+    /// every chunk up to the end of page `n`. Panics if `page_size` is zero.
+    pub fn pages(&self, page_size: u32) -> impl Iterator<Item = Bytes> + '_ {
+        if page_size == 0 {
+            panic!("Page size must be non-zero");
         }
+        let mut chunks = self.iter();
+        let mut pending = Bytes::new(self.env);
+        core::iter::from_fn(move || {
+            while pending.len() < page_size {
+                match chunks.next() {
+                    Some(chunk) => pending.append(&chunk),
+                    None if pending.is_empty() => return None,
+                    None => return Some(core::mem::replace(&mut pending, Bytes::new(self.env))),
+                }
+            }
+            let page = pending.slice(..page_size);
+            pending = pending.slice(page_size..);
+            Some(page)
+        })
+    }
 
-        // Insert new chunk
-        let key = ChonkKey::Chunk(self.id.clone(), index);
-        let data_len = data.len();
-        self.env.storage().persistent().set(&key, &data);
-
-        meta.count += 1;
-        meta.total_bytes += data_len;
-        meta.version += 1;
-        self.save_meta(&meta);
+    /// How many more bytes `append(_, max_chunk_size)` would merge into the
+    /// last chunk before starting a new one; 0 if empty or the last chunk is
+    /// full.
+    ///
+    /// Chunk lengths aren't kept in the metadata, so this reads the last chunk.
+    pub fn last_chunk_remaining(&self, max_chunk_size: u32) -> u32 {
+        let meta = self.meta();
+        let Some(last) = meta.count.checked_sub(1) else {
+            return 0;
+        };
+        self.load_chunk(&meta, last)
+            .map_or(0, |chunk| max_chunk_size.saturating_sub(chunk.len()))
     }
 
-    /// Remove a chunk at index (shifts subsequent chunks)
-    pub fn remove(&self, index: u32) -> Option<Bytes> {
-        let mut meta = self.meta();
-        if index >= meta.count {
-            return None;
-        }
+    /// Iterate from `index` down to chunk 0, clamping `index` to the last chunk
+    pub fn reverse_iter_from(&self, index: u32) -> Rev<ChonkIter<'_>> {
+        let meta = self.meta();
+        let end = core::cmp::min(index.saturating_add(1), meta.count);
+        ChonkIter::over(self.clone(), meta, end).rev()
+    }
 
-        // Get the chunk being removed
-        let key = ChonkKey::Chunk(self.id.clone(), index);
-        let removed: Option<Bytes> = self.env.storage().persistent().get(&key);
+    /// Assemble all chunks into a single Bytes
+    /// Warning: May hit execution limits for very large content
+    pub fn assemble(&self) -> Bytes {
+        let mut result = Bytes::new(self.env);
+        self.assemble_into(&mut result);
+        result
+    }
 
-        // Shift subsequent chunks
-        for i in index..(meta.count - 1) {
-            let from_key = ChonkKey::Chunk(self.id.clone(), i + 1);
-            let to_key = ChonkKey::Chunk(self.id.clone(), i);
-            if let Some(chunk) = self.env.storage().persistent().get::<_, Bytes>(&from_key) {
-                self.env.storage().persistent().set(&to_key, &chunk);
+    /// Assemble at most `max_bytes` of the content, truncating at exactly
+    /// `max_bytes` if it is longer. Chunks past the limit are not read.
+    pub fn assemble_up_to(&self, max_bytes: u32) -> Bytes {
+        let mut result = Bytes::new(self.env);
+        for chunk in self.iter() {
+            let remaining = max_bytes - result.len();
+            if chunk.len() >= remaining {
+                result.append(&chunk.slice(..remaining));
+                break;
             }
+            result.append(&chunk);
         }
+        result
+    }
 
-        // Remove last slot
-        let last_key = ChonkKey::Chunk(self.id.clone(), meta.count - 1);
-        self.env.storage().persistent().remove(&last_key);
+    /// The first `n` logical bytes, or all of them if there are fewer. Same
+    /// as `assemble_up_to`.
+    pub fn head(&self, n: u32) -> Bytes {
+        self.assemble_up_to(n)
+    }
 
-        // Update metadata
-        if let Some(ref data) = removed {
-            meta.total_bytes -= data.len();
+    /// The last `n` logical bytes, or all of them if there are fewer. Reads
+    /// chunks from the end, stopping once it has enough.
+    pub fn tail(&self, n: u32) -> Bytes {
+        let mut pieces = Vec::new(self.env);
+        let mut len = 0u32;
+        for chunk in self.iter().rev() {
+            if len >= n {
+                break;
+            }
+            len = len.saturating_add(chunk.len());
+            pieces.push_front(chunk);
         }
-        meta.count -= 1;
-        meta.version += 1;
-        self.save_meta(&meta);
 
-        removed
+        let mut result = Bytes::new(self.env);
+        for piece in pieces.iter() {
+            result.append(&piece);
+        }
+        result.slice(result.len().saturating_sub(n)..)
     }
 
-    /// Remove all chunks
-    pub fn clear(&self) {
-        let meta = self.meta();
+    /// Assemble the chunks last to first into a single Bytes. Each chunk's
+    /// own bytes keep their order.
+    pub fn assemble_reversed(&self) -> Bytes {
+        let mut result = Bytes::new(self.env);
+        for chunk in self.iter().rev() {
+            result.append(&chunk);
+        }
+        result
+    }
 
-        // Remove all chunks
-        for i in 0..meta.count {
-            let key = ChonkKey::Chunk(self.id.clone(), i);
-            self.env.storage().persistent().remove(&key);
+    /// Append all chunks to `buffer`, after whatever it already holds
+    pub fn assemble_into(&self, buffer: &mut Bytes) {
+        for chunk in self.iter() {
+            buffer.append(&chunk);
         }
+    }
 
-        // Remove metadata
-        let meta_key = ChonkKey::Meta(self.id.clone());
-        self.env.storage().persistent().remove(&meta_key);
+    // ─── Search Operations ─────────────────────────────────
+
+    /// Count the chunks for which `f` returns true, reading one chunk at a
+    /// time
+    pub fn count_matching<F: FnMut(&Bytes) -> bool>(&self, mut f: F) -> u32 {
+        self.iter().filter(|chunk| f(chunk)).count() as u32
     }
 
-    // ─── Bulk Operations ───────────────────────────────────
+    /// Count non-overlapping occurrences of `pattern` in the logical
+    /// content, scanning left to right across chunk boundaries.
+    /// Panics if `pattern` is empty.
+    pub fn count_occurrences(&self, pattern: &Bytes) -> u32 {
+        let mut count = 0;
+        self.scan_matches(pattern, |_| count += 1);
+        count
+    }
 
-    /// Write content, automatically chunking at specified size
-    pub fn write_chunked(&self, content: Bytes, chunk_size: u32) {
-        // Clear existing content
-        self.clear();
+    /// Find the logical offset of the first occurrence of `byte`, reading
+    /// chunks only until it is found
+    ///
+    /// The offset is a `u64` rather than a `u32` on purpose: since
+    /// `total_bytes` is a `u64`, a match can sit past `u32::MAX`.
+    pub fn index_of_byte(&self, byte: u8) -> Option<u64> {
+        let mut offset = 0u64;
+        for chunk in self.iter() {
+            if let Some(i) = chunk.iter().position(|b| b == byte) {
+                return Some(offset + i as u64);
+            }
+            offset += chunk.len() as u64;
+        }
+        None
+    }
 
-        let content_len = content.len();
-        if content_len == 0 {
-            return;
+    /// Check whether the content equals `other`, without assembling it.
+    ///
+    /// Compares `total_bytes` first, so a length mismatch reads no chunks;
+    /// otherwise chunks are read only until the first difference.
+    pub fn equal_bytes(&self, other: &Bytes) -> bool {
+        if self.total_bytes() != other.len() as u64 {
+            return false;
         }
 
         let mut offset = 0u32;
-        while offset < content_len {
-            let end = core::cmp::min(offset + chunk_size, content_len);
-            let chunk = content.slice(offset..end);
-            self.push(chunk);
+        for chunk in self.iter() {
+            let end = offset + chunk.len();
+            if end > other.len() || other.slice(offset..end) != chunk {
+                return false;
+            }
             offset = end;
         }
+        offset == other.len()
     }
 
-    /// Append content to last chunk or create new if it would exceed max size
-    pub fn append(&self, content: Bytes, max_chunk_size: u32) {
-        let meta = self.meta();
+    /// Check that every chunk compares as no greater than the next under
+    /// `cmp`, reading chunks only until the order is broken. Empty and
+    /// single-chunk collections are sorted.
+    pub fn is_sorted_by<F: FnMut(&Bytes, &Bytes) -> Ordering>(&self, mut cmp: F) -> bool {
+        let mut chunks = self.iter();
+        let Some(mut prev) = chunks.next() else {
+            return true;
+        };
+        for chunk in chunks {
+            if cmp(&prev, &chunk) == Ordering::Greater {
+                return false;
+            }
+            prev = chunk;
+        }
+        true
+    }
 
-        if meta.count == 0 {
-            self.push(content);
-            return;
+    /// Check that no chunk is longer than the next. See `is_sorted_by`.
+    pub fn is_sorted_by_len(&self) -> bool {
+        self.is_sorted_by(|a, b| a.len().cmp(&b.len()))
+    }
+
+    /// Stream the logical content, calling `on_match` with the offset of each
+    /// non-overlapping occurrence of `pattern`. Only the bytes that could
+    /// still begin a match are carried from one chunk into the next.
+    fn scan_matches(&self, pattern: &Bytes, mut on_match: impl FnMut(u64)) {
+        if pattern.is_empty() {
+            panic!("Pattern must not be empty");
         }
 
-        let last_index = meta.count - 1;
-        if let Some(last_chunk) = self.get(last_index) {
-            let new_len = last_chunk.len() + content.len();
-            if new_len <= max_chunk_size {
-                // Append to existing chunk
-                let mut combined = Bytes::new(self.env);
-                combined.append(&last_chunk);
-                combined.append(&content);
-                self.set(last_index, combined);
-            } else {
-                // Create new chunk
-                self.push(content);
-            }
-        } else {
-            self.push(content);
+        let mut window = Bytes::new(self.env);
+        let mut window_start = 0u64;
+
+        for chunk in self.iter() {
+            window.append(&chunk);
+            let next = find_matches(&window, pattern, |i| on_match(window_start + i as u64));
+
+            // Every position before `next` has been ruled out or consumed
+            window_start += next as u64;
+            window = window.slice(next..);
         }
     }
+
+    // ─── Layout ────────────────────────────────────────────
+
+    /// Storage holding this collection's entries
+    fn storage(&self) -> Store<'a> {
+        Store::new(self.env, self.storage_kind).with_ttl(self.ttl)
+    }
+
+    /// Metadata of an empty collection in this handle's layout and flags
+    fn empty_meta(&self) -> ChonkMeta {
+        let layout = match self.layout {
+            LayoutKind::Dense => ChonkLayout::Dense,
+            LayoutKind::Gapped => ChonkLayout::Gapped(ChonkSlots {
+                map: Vec::new(self.env),
+                next: 0,
+            }),
+            LayoutKind::Linked => ChonkLayout::Linked(ChonkLinks {
+                head: None,
+                tail: None,
+                next: 0,
+            }),
+        };
+        ChonkMeta {
+            layout,
+            append_only: self.append_only,
+            events: self.events,
+            journal: self.journal,
+            hashed: self.hashed,
+            chunk_size: self.chunk_size,
+            owner: self.owner.clone(),
+            ..ChonkMeta::new()
+        }
+    }
+
+    /// Read chunk `index` through the layout recorded in `meta`
+    pub(crate) fn load_chunk(&self, meta: &ChonkMeta, index: u32) -> Option<Bytes> {
+        if index >= meta.count {
+            return None;
+        }
+        let chunk = match &meta.layout {
+            ChonkLayout::Dense => self.storage().get(&ChonkKey::Chunk(self.id.clone(), index)),
+            ChonkLayout::Gapped(slots) => {
+                let key = ChonkKey::Slot(self.id.clone(), slots.map.get_unchecked(index));
+                self.storage().get(&key)
+            }
+            ChonkLayout::Linked(links) => {
+                let node = self.node_at(links, meta.count, index);
+                self.load_node(node).map(|node| node.data)
+            }
+        };
+        chunk.map(|chunk| self.decode(chunk))
+    }
+
+    /// Chunk data as stored, through the handle's codec if it has one
+    fn encode(&self, data: &Bytes) -> Bytes {
+        match self.codec {
+            Some(codec) => codec.encode(data),
+            None => data.clone(),
+        }
+    }
+
+    /// Chunk data as read from storage, through the handle's codec if it
+    /// has one
+    pub(crate) fn decode(&self, data: Bytes) -> Bytes {
+        match self.codec {
+            Some(codec) => codec.decode(&data),
+            None => data,
+        }
+    }
+
+    /// Overwrite chunk `index`, which must be below `meta.count`
+    fn store_chunk(&self, meta: &ChonkMeta, index: u32, data: &Bytes) {
+        let data = &self.encode(data);
+        match &meta.layout {
+            ChonkLayout::Dense => {
+                let key = ChonkKey::Chunk(self.id.clone(), index);
+                self.storage().set(&key, data);
+            }
+            ChonkLayout::Gapped(slots) => {
+                let key = ChonkKey::Slot(self.id.clone(), slots.map.get_unchecked(index));
+                self.storage().set(&key, data);
+            }
+            ChonkLayout::Linked(links) => {
+                let id = self.node_at(links, meta.count, index);
+                let mut node = self.load_node(id).expect("linked chunk missing");
+                node.data = data.clone();
+                self.store_node(id, &node);
+            }
+        }
+    }
+
+    /// Insert `chunks` at `index`, advancing `meta.count` by their number.
+    ///
+    /// Dense collections shift the tail up to make room. Gapped collections
+    /// write fresh slots and splice them into the map, and linked ones link
+    /// fresh nodes between their neighbours, leaving every other chunk
+    /// entry untouched. Nothing is written on error.
+    fn insert_chunks(
+        &self,
+        meta: &mut ChonkMeta,
+        index: u32,
+        chunks: &Vec<Bytes>,
+    ) -> Result<(), ChonkError> {
+        let count = meta.count;
+        let n = chunks.len();
+        meta.count = checked_add(count, n)?;
+
+        match &mut meta.layout {
+            ChonkLayout::Dense => {
+                for i in (index..count).rev() {
+                    let from_key = ChonkKey::Chunk(self.id.clone(), i);
+                    let to_key = ChonkKey::Chunk(self.id.clone(), i + n);
+                    if let Some(chunk) = self.storage().get::<Bytes>(&from_key) {
+                        self.storage().set(&to_key, &chunk);
+                    }
+                }
+                for (offset, chunk) in chunks.iter().enumerate() {
+                    let key = ChonkKey::Chunk(self.id.clone(), index + offset as u32);
+                    self.storage().set(&key, &self.encode(&chunk));
+                }
+            }
+            ChonkLayout::Gapped(slots) => {
+                let first = slots.next;
+                slots.next = checked_add(first, n)?;
+                for (offset, chunk) in chunks.iter().enumerate() {
+                    let slot = first + offset as u32;
+                    slots.map.insert(index + offset as u32, slot);
+                    self.storage()
+                        .set(&ChonkKey::Slot(self.id.clone(), slot), &self.encode(&chunk));
+                }
+            }
+            ChonkLayout::Linked(links) => {
+                if n == 0 {
+                    return Ok(());
+                }
+                let first = links.next;
+                links.next = checked_add(first, n)?;
+
+                let prev = index.checked_sub(1).map(|i| self.node_at(links, count, i));
+                let next = (index < count).then(|| self.node_at(links, count, index));
+                for (offset, chunk) in chunks.iter().enumerate() {
+                    let id = first + offset as u32;
+                    let node = ChonkNode {
+                        data: self.encode(&chunk),
+                        prev: if offset == 0 { prev } else { Some(id - 1) },
+                        next: if id + 1 == first + n {
+                            next
+                        } else {
+                            Some(id + 1)
+                        },
+                    };
+                    self.store_node(id, &node);
+                }
+                self.link(links, prev, Some(first), next, Some(first + n - 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop chunks `[start, end)`, which must lie within `meta.count`,
+    /// shrinking `meta.count` to match.
+    ///
+    /// Dense collections shift the tail down over the range. Gapped
+    /// collections only remove the range's slots and map entries, and
+    /// linked ones unlink the range's nodes from their neighbours.
+    fn close_gap(&self, meta: &mut ChonkMeta, start: u32, end: u32) {
+        let count = meta.count;
+        let n = end - start;
+        meta.count = count - n;
+
+        match &mut meta.layout {
+            ChonkLayout::Dense => {
+                for i in end..count {
+                    let from_key = ChonkKey::Chunk(self.id.clone(), i);
+                    let to_key = ChonkKey::Chunk(self.id.clone(), i - n);
+                    if let Some(chunk) = self.storage().get::<Bytes>(&from_key) {
+                        self.storage().set(&to_key, &chunk);
+                    }
+                }
+
+                // Remove the now-unused trailing keys
+                for i in (count - n)..count {
+                    let key = ChonkKey::Chunk(self.id.clone(), i);
+                    self.storage().remove(&key);
+                }
+            }
+            ChonkLayout::Gapped(slots) => {
+                for _ in start..end {
+                    let key = ChonkKey::Slot(self.id.clone(), slots.map.get_unchecked(start));
+                    self.storage().remove(&key);
+                    slots.map.remove_unchecked(start);
+                }
+            }
+            ChonkLayout::Linked(links) => {
+                if n == 0 {
+                    return;
+                }
+                let mut id = self.node_at(links, count, start);
+                let mut node = self.load_node(id).expect("linked chunk missing");
+                let prev = node.prev;
+                for _ in 1..n {
+                    self.storage().remove(&ChonkKey::Node(self.id.clone(), id));
+                    id = node.next.expect("linked chunk missing");
+                    node = self.load_node(id).expect("linked chunk missing");
+                }
+                self.storage().remove(&ChonkKey::Node(self.id.clone(), id));
+                self.link(links, prev, node.next, node.next, prev);
+            }
+        }
+    }
+
+    /// Remove every chunk and refill the collection with `chunks`, keeping
+    /// its layout. Returns the updated metadata for the caller to save.
+    /// Nothing is written on error.
+    fn replace_contents(
+        &self,
+        mut meta: ChonkMeta,
+        chunks: &Vec<Bytes>,
+    ) -> Result<ChonkMeta, ChonkError> {
+        let mut total_bytes = 0;
+        for chunk in chunks.iter() {
+            self.check_chunk_len(&chunk)?;
+            total_bytes = add_bytes(total_bytes, chunk.len())?;
+        }
+
+        let count = meta.count;
+        self.close_gap(&mut meta, 0, count);
+
+        // Nothing remains, so slot and node numbering can restart from zero
+        meta.layout = match meta.layout {
+            ChonkLayout::Dense => ChonkLayout::Dense,
+            ChonkLayout::Gapped(_) => ChonkLayout::Gapped(ChonkSlots {
+                map: Vec::new(self.env),
+                next: 0,
+            }),
+            ChonkLayout::Linked(_) => ChonkLayout::Linked(ChonkLinks {
+                head: None,
+                tail: None,
+                next: 0,
+            }),
+        };
+
+        meta.total_bytes = total_bytes;
+        self.insert_chunks(&mut meta, 0, chunks)?;
+        Ok(meta)
+    }
+
+    /// Handle on collection `id` with this handle's cache, storage kind,
+    /// TTL policy, limits, layout and codec. Sharing the metadata cache
+    /// means writing through it refreshes whatever the cache holds for `id`
+    /// instead of leaving it stale.
+    ///
+    /// Policy recorded in metadata (append-only, events, journaling,
+    /// hashing, chunk size and owner) belongs to this collection and is not
+    /// carried over.
+    fn sibling(&self, id: Symbol) -> Chonk<'a> {
+        Chonk {
+            id,
+            append_only: false,
+            events: false,
+            journal: false,
+            chunk_size: None,
+            owner: None,
+            hashed: false,
+            ..self.clone()
+        }
+    }
+
+    /// Read a node of a linked collection
+    pub(crate) fn load_node(&self, id: u32) -> Option<ChonkNode> {
+        self.storage().get(&ChonkKey::Node(self.id.clone(), id))
+    }
+
+    fn store_node(&self, id: u32, node: &ChonkNode) {
+        self.storage()
+            .set(&ChonkKey::Node(self.id.clone(), id), node);
+    }
+
+    /// Id of the node holding chunk `index`, walking from whichever end of
+    /// the list is closer. Each step reads one node.
+    pub(crate) fn node_at(&self, links: &ChonkLinks, count: u32, index: u32) -> u32 {
+        let missing = || panic!("linked chunk missing");
+        if index < count / 2 {
+            let mut id = links.head.unwrap_or_else(missing);
+            for _ in 0..index {
+                id = self
+                    .load_node(id)
+                    .and_then(|n| n.next)
+                    .unwrap_or_else(missing);
+            }
+            id
+        } else {
+            let mut id = links.tail.unwrap_or_else(missing);
+            for _ in index + 1..count {
+                id = self
+                    .load_node(id)
+                    .and_then(|n| n.prev)
+                    .unwrap_or_else(missing);
+            }
+            id
+        }
+    }
+
+    /// Point `prev` forward to `after` and `next` back to `before`, or the
+    /// list's head and tail when there is no such neighbour
+    fn link(
+        &self,
+        links: &mut ChonkLinks,
+        prev: Option<u32>,
+        after: Option<u32>,
+        next: Option<u32>,
+        before: Option<u32>,
+    ) {
+        match prev {
+            Some(id) => {
+                let mut node = self.load_node(id).expect("linked chunk missing");
+                node.next = after;
+                self.store_node(id, &node);
+            }
+            None => links.head = after,
+        }
+        match next {
+            Some(id) => {
+                let mut node = self.load_node(id).expect("linked chunk missing");
+                node.prev = before;
+                self.store_node(id, &node);
+            }
+            None => links.tail = before,
+        }
+    }
+
+    // ─── Write Operations ──────────────────────────────────
+
+    /// Save metadata, keeping the count entry in sync
+    ///
+    /// Every write saves the metadata or clears it, so requiring the owner's
+    /// authorization here and in `clear_counting` covers them all.
+    fn save_meta(&self, meta: &ChonkMeta) {
+        if let Some(owner) = &meta.owner {
+            owner.require_auth();
+        }
+
+        // The chunks are already written, so the digest can be taken now
+        let rehashed;
+        let meta = if meta.hashed && !hash_is_current(meta) {
+            let mut updated = meta.clone();
+            let digest = self.digest(ChonkIter::over(self.clone(), meta.clone(), meta.count));
+            updated.content_hash = Some(digest);
+            updated.hash_version = meta.version;
+            rehashed = updated;
+            &rehashed
+        } else {
+            meta
+        };
+
+        let key = ChonkKey::Meta(self.id.clone());
+        self.storage().set(&key, meta);
+
+        // Most writes (set, overwrite, append into the last chunk) keep the
+        // count, so only rewrite the count entry when it changes
+        let count_key = ChonkKey::Count(self.id.clone());
+        if self.storage().get::<u32>(&count_key) != Some(meta.count) {
+            self.storage().set(&count_key, &meta.count);
+        }
+
+        if let Some(cache) = self.cache {
+            cache.set(&self.id, self.storage_kind, meta.clone());
+        }
+    }
+
+    /// Chain each chunk into a SHA-256 digest: starting from 32 zero bytes,
+    /// `digest = sha256(digest || chunk)`
+    fn digest(&self, chunks: ChonkIter) -> BytesN<32> {
+        let mut digest = BytesN::from_array(self.env, &[0; 32]);
+        for chunk in chunks {
+            digest = self.fold_digest(digest, &chunk);
+        }
+        digest
+    }
+
+    /// Chain one more chunk into `digest`
+    fn fold_digest(&self, digest: BytesN<32>, chunk: &Bytes) -> BytesN<32> {
+        let mut input = Bytes::from(digest);
+        input.append(chunk);
+        self.env.crypto().sha256(&input).to_bytes()
+    }
+
+    /// Reject a chunk whose stored form is longer than the configured
+    /// `max_chunk_size`, before it reaches the host's own ledger entry size
+    /// limit
+    fn check_chunk_len(&self, data: &Bytes) -> Result<(), ChonkError> {
+        match self.max_chunk_size {
+            Some(max) if self.encode(data).len() > max => Err(ChonkError::ChunkTooLarge),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject a write touching chunks at or after `index` of an append-only
+    /// collection. Adding chunks at the end is always allowed.
+    pub(crate) fn check_append_only(&self, meta: &ChonkMeta, index: u32) -> Result<(), ChonkError> {
+        if meta.append_only && index < meta.count {
+            return Err(ChonkError::AppendOnly);
+        }
+        Ok(())
+    }
+
+    /// Panicking form of `check_append_only`
+    fn ensure_append_only(&self, meta: &ChonkMeta, index: u32) {
+        self.check_append_only(meta, index)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Record a write that produced `meta.version` in the journal, if the
+    /// collection is journaled, and emit its event
+    fn publish(&self, meta: &ChonkMeta, action: Symbol, index: u32) {
+        if meta.journal {
+            let key = ChonkKey::Change(self.id.clone(), meta.version);
+            self.storage().set(&key, &index);
+
+            // Drop the record that just fell out of the window
+            if let Some(stale) = meta.version.checked_sub(JOURNAL_WINDOW).filter(|&v| v > 0) {
+                self.storage()
+                    .remove(&ChonkKey::Change(self.id.clone(), stale));
+            }
+        }
+        self.emit(meta, action, index);
+    }
+
+    /// Emit a `(chonk, action, id)` event carrying the first affected index,
+    /// if the collection has events enabled
+    fn emit(&self, meta: &ChonkMeta, action: Symbol, index: u32) {
+        if meta.events {
+            let topics = (symbol_short!("chonk"), action, self.id.clone());
+            self.env.events().publish(topics, index);
+        }
+    }
+
+    /// Append a chunk to the end, returns the new index
+    pub fn push(&self, data: Bytes) -> u32 {
+        self.try_push(data)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Append a chunk copied from `data`, returns the new index. Panics like
+    /// `push` on error.
+    pub fn push_slice(&self, data: &[u8]) -> u32 {
+        self.try_push(Bytes::from_slice(self.env, data))
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Append a chunk copied from each of `slices`, in order, with a single
+    /// metadata update and version bump, like `insert_many` at the end
+    pub fn extend_from_slices(&self, slices: &[&[u8]]) {
+        let mut chunks = Vec::new(self.env);
+        for slice in slices {
+            chunks.push_back(Bytes::from_slice(self.env, slice));
+        }
+        self.insert_many(self.count(), chunks);
+    }
+
+    /// Append a chunk, returning an error instead of exceeding the configured
+    /// limits or overflowing the metadata. Nothing is written on error.
+    pub fn try_push(&self, data: Bytes) -> Result<u32, ChonkError> {
+        self.check_chunk_len(&data)?;
+
+        let mut meta = self.meta();
+        let index = meta.count;
+        // An empty collection's digest is the chain's zero start
+        let digest = match &meta.content_hash {
+            _ if !meta.hashed => None,
+            Some(digest) if hash_is_current(&meta) => Some(digest.clone()),
+            _ if index == 0 => Some(BytesN::from_array(self.env, &[0; 32])),
+            _ => None,
+        };
+
+        meta.total_bytes = add_bytes(meta.total_bytes, data.len())?;
+        if self
+            .max_total_bytes
+            .is_some_and(|cap| meta.total_bytes > cap)
+        {
+            return Err(ChonkError::StorageLimitExceeded);
+        }
+        meta.version = bump_version(meta.version)?;
+
+        // Appending extends the digest chain, so no rehash is needed
+        if let Some(digest) = digest {
+            meta.content_hash = Some(self.fold_digest(digest, &data));
+            meta.hash_version = meta.version;
+        }
+
+        self.insert_chunks(&mut meta, index, &Vec::from_array(self.env, [data]))?;
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("push"), index);
+
+        Ok(index)
+    }
+
+    /// Replace a specific chunk
+    pub fn set(&self, index: u32, data: Bytes) {
+        self.try_set(index, data)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Replace a specific chunk, returning an error instead of panicking
+    pub fn try_set(&self, index: u32, data: Bytes) -> Result<(), ChonkError> {
+        let meta = self.meta();
+        self.check_append_only(&meta, index)?;
+        self.overwrite(meta, index, data).map(|_| ())
+    }
+
+    /// Replace a specific chunk, returning the chunk it replaced. Like
+    /// `remove`, an out-of-bounds index yields `None` and writes nothing.
+    pub fn replace(&self, index: u32, data: Bytes) -> Option<Bytes> {
+        let meta = self.meta();
+        if index >= meta.count {
+            return None;
+        }
+        self.ensure_append_only(&meta, index);
+        self.overwrite(meta, index, data)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Replace chunk `index` without the append-only check, returning the
+    /// previous chunk
+    fn overwrite(
+        &self,
+        mut meta: ChonkMeta,
+        index: u32,
+        data: Bytes,
+    ) -> Result<Option<Bytes>, ChonkError> {
+        if index >= meta.count {
+            return Err(ChonkError::IndexOutOfBounds);
+        }
+        self.check_chunk_len(&data)?;
+
+        // Adjust total_bytes
+        let old_data = self.load_chunk(&meta, index);
+        if let Some(ref old_data) = old_data {
+            meta.total_bytes = sub_bytes(meta.total_bytes, old_data.len())?;
+        }
+        meta.total_bytes = add_bytes(meta.total_bytes, data.len())?;
+        meta.version = bump_version(meta.version)?;
+
+        self.store_chunk(&meta, index, &data);
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("set"), index);
+        Ok(old_data)
+    }
+
+    /// Overwrite `data.len()` logical bytes starting at `offset`, rewriting
+    /// only the chunks the range touches. Bytes running past the current end
+    /// are added as a new last chunk. Bumps the version once.
+    ///
+    /// Fails with `IndexOutOfBounds` if `offset` is past `total_bytes`, with
+    /// `AppendOnly` if the write overlaps existing content of an append-only
+    /// collection, and like `try_push` if the new last chunk breaks the
+    /// handle's limits. Nothing is written on error.
+    pub fn write_at(&self, offset: u32, data: Bytes) -> Result<(), ChonkError> {
+        let mut meta = self.meta();
+        let total = meta.total_bytes;
+        let start = offset as u64;
+        let end = start + data.len() as u64;
+        if start > total {
+            return Err(ChonkError::IndexOutOfBounds);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        // New contents of every chunk overlapping [start, end)
+        let mut changes: Vec<(u32, Bytes)> = Vec::new(self.env);
+        let mut pos = 0u64;
+        for (index, chunk) in self.iter().enumerate() {
+            let (chunk_start, chunk_end) = (pos, pos + chunk.len() as u64);
+            pos = chunk_end;
+            if chunk_end <= start || chunk.is_empty() {
+                continue;
+            }
+            if chunk_start >= end {
+                break;
+            }
+
+            let from = (core::cmp::max(start, chunk_start) - chunk_start) as u32;
+            let to = (core::cmp::min(end, chunk_end) - chunk_start) as u32;
+            let data_from = (chunk_start + from as u64 - start) as u32;
+            let mut updated = chunk.slice(..from);
+            updated.append(&data.slice(data_from..data_from + (to - from)));
+            updated.append(&chunk.slice(to..));
+            changes.push_back((index as u32, updated));
+        }
+        let index = changes.first().map_or(meta.count, |(index, _)| index);
+        if start < total {
+            self.check_append_only(&meta, index)?;
+        }
+
+        // The bytes past the end become a new last chunk, held to the same
+        // limits as `try_push`
+        let overflow = (end > total).then(|| data.slice((total - start) as u32..));
+        if let Some(overflow) = &overflow {
+            self.check_chunk_len(overflow)?;
+            meta.total_bytes = add_bytes(meta.total_bytes, overflow.len())?;
+            if self
+                .max_total_bytes
+                .is_some_and(|cap| meta.total_bytes > cap)
+            {
+                return Err(ChonkError::StorageLimitExceeded);
+            }
+        }
+
+        meta.version = bump_version(meta.version)?;
+        if let Some(overflow) = overflow {
+            let count = meta.count;
+            self.insert_chunks(&mut meta, count, &Vec::from_array(self.env, [overflow]))?;
+        }
+        for (index, updated) in changes.iter() {
+            self.store_chunk(&meta, index, &updated);
+        }
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("set"), index);
+        Ok(())
+    }
+
+    /// Overwrite `data.len()` existing logical bytes starting at `offset`,
+    /// like `write_at` but never growing the content: `total_bytes` and the
+    /// chunk lengths stay the same.
+    ///
+    /// Fails with `IndexOutOfBounds`, writing nothing, if the span would run
+    /// past the end.
+    pub fn set_range(&self, offset: u32, data: Bytes) -> Result<(), ChonkError> {
+        if offset as u64 + data.len() as u64 > self.total_bytes() {
+            return Err(ChonkError::IndexOutOfBounds);
+        }
+        self.write_at(offset, data)
+    }
+
+    /// Replace chunk `index` with `len` copies of `byte`. Panics like `set`
+    /// if `index` is out of bounds.
+    pub fn fill(&self, index: u32, byte: u8, len: u32) {
+        self.try_set(index, repeat_byte(self.env, byte, len))
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Overwrite `len` logical bytes starting at `offset` with `byte`,
+    /// clamped to the end of the content, rewriting only the chunks the
+    /// range touches like `set_range`. `total_bytes` and chunk lengths are
+    /// unchanged. Panics with `AppendOnly` on an append-only collection.
+    pub fn fill_range(&self, offset: u32, byte: u8, len: u32) {
+        let available = self.total_bytes().saturating_sub(offset as u64);
+        let len = core::cmp::min(len as u64, available) as u32;
+        if len == 0 {
+            return;
+        }
+        self.write_at(offset, repeat_byte(self.env, byte, len))
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Replace a chunk only if it currently equals `expected`.
+    /// Returns whether the swap happened.
+    pub fn compare_and_set(
+        &self,
+        index: u32,
+        expected: &Bytes,
+        new: Bytes,
+    ) -> Result<bool, ChonkError> {
+        let meta = self.meta();
+        if index >= meta.count {
+            return Err(ChonkError::IndexOutOfBounds);
+        }
+        self.check_append_only(&meta, index)?;
+
+        if self.get(index).as_ref() != Some(expected) {
+            return Ok(false);
+        }
+
+        self.try_set(index, new)?;
+        Ok(true)
+    }
+
+    /// Write `new` to chunk `index` only if it still holds `old`, failing
+    /// with `VersionConflict` (and writing nothing) if it doesn't. Like
+    /// `compare_and_set`, with a stale chunk reported as an error.
+    pub fn set_if_unchanged(&self, index: u32, old: Bytes, new: Bytes) -> Result<(), ChonkError> {
+        if self.compare_and_set(index, &old, new)? {
+            Ok(())
+        } else {
+            Err(ChonkError::VersionConflict)
+        }
+    }
+
+    /// Insert a chunk at index (shifts subsequent chunks)
+    ///
+    /// Reads and writes the metadata once and, in a dense collection, moves
+    /// exactly `count - index` chunks, so the cost grows with the distance
+    /// from the end. Prefer `push`, `insert_many` or a collection opened
+    /// with `open_gapped` for large collections.
+    pub fn insert(&self, index: u32, data: Bytes) {
+        self.try_insert(index, data)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Insert a chunk at index, returning an error instead of panicking
+    pub fn try_insert(&self, index: u32, data: Bytes) -> Result<(), ChonkError> {
+        self.insert_one(index, data).map(|_| ())
+    }
+
+    /// Insert a chunk at index like `insert`, returning the collection's new
+    /// `total_bytes` so callers tracking storage don't need to read `meta()`
+    pub fn insert_reporting(&self, index: u32, data: Bytes) -> u64 {
+        self.insert_one(index, data)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+            .total_bytes
+    }
+
+    /// Insert a chunk at index, returning the saved metadata
+    fn insert_one(&self, index: u32, data: Bytes) -> Result<ChonkMeta, ChonkError> {
+        let mut meta = self.meta();
+        if index > meta.count {
+            return Err(ChonkError::IndexOutOfBounds);
+        }
+        self.check_append_only(&meta, index)?;
+        self.check_chunk_len(&data)?;
+
+        meta.total_bytes = add_bytes(meta.total_bytes, data.len())?;
+        meta.version = bump_version(meta.version)?;
+
+        self.insert_chunks(&mut meta, index, &Vec::from_array(self.env, [data]))?;
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("insert"), index);
+        Ok(meta)
+    }
+
+    /// Insert several chunks starting at index. The tail is shifted once by
+    /// `chunks.len()` and the metadata is updated once, instead of paying a
+    /// full shift per chunk as repeated `insert` calls would.
+    pub fn insert_many(&self, index: u32, chunks: Vec<Bytes>) {
+        let mut meta = self.meta();
+        if index > meta.count {
+            panic_with_error!(self.env, ChonkError::IndexOutOfBounds);
+        }
+        self.ensure_append_only(&meta, index);
+
+        if chunks.is_empty() {
+            return;
+        }
+
+        for chunk in chunks.iter() {
+            self.check_chunk_len(&chunk)
+                .unwrap_or_else(|e| panic_with_error!(self.env, e));
+            meta.total_bytes = add_bytes(meta.total_bytes, chunk.len())
+                .unwrap_or_else(|e| panic_with_error!(self.env, e));
+        }
+
+        // Open and fill a gap of `chunks.len()` in one pass
+        self.insert_chunks(&mut meta, index, &chunks)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("insert"), index);
+    }
+
+    /// Remove a chunk at index (shifts subsequent chunks)
+    pub fn remove(&self, index: u32) -> Option<Bytes> {
+        self.try_remove(index)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Remove a chunk at index, returning an error instead of underflowing
+    /// the metadata. Like `remove`, an out-of-bounds index yields `Ok(None)`.
+    pub fn try_remove(&self, index: u32) -> Result<Option<Bytes>, ChonkError> {
+        let mut meta = self.meta();
+        if index >= meta.count {
+            return Ok(None);
+        }
+        self.check_append_only(&meta, index)?;
+
+        // Get the chunk being removed
+        let removed = self.load_chunk(&meta, index);
+
+        // Validate the metadata update before touching storage
+        if let Some(ref data) = removed {
+            meta.total_bytes = sub_bytes(meta.total_bytes, data.len())?;
+        }
+        meta.version = bump_version(meta.version)?;
+
+        self.close_gap(&mut meta, index, index + 1);
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("remove"), index);
+        Ok(removed)
+    }
+
+    /// Remove chunks `[start, end)`, shifting the tail down in one pass and
+    /// updating the metadata once. `end` is clamped to `count`.
+    ///
+    /// Unlike removing one by one, nothing is returned; removed chunks are
+    /// only read to account for their length in `total_bytes`.
+    pub fn remove_range(&self, start: u32, end: u32) {
+        let mut meta = self.meta();
+        let end = core::cmp::min(end, meta.count);
+        if start >= end {
+            return;
+        }
+        self.ensure_append_only(&meta, start);
+
+        for i in start..end {
+            if let Some(chunk) = self.load_chunk(&meta, i) {
+                meta.total_bytes = sub_bytes(meta.total_bytes, chunk.len())
+                    .unwrap_or_else(|e| panic_with_error!(self.env, e));
+            }
+        }
+
+        self.close_gap(&mut meta, start, end);
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("remove"), start);
+    }
+
+    /// Move chunk `from` to index `to`, shifting the chunks in between by
+    /// one. Bumps the version once; `from == to` writes nothing.
+    ///
+    /// Dense collections shift the intervening chunks in a single pass.
+    /// Gapped collections only update the slot map, and linked ones relink
+    /// the moved chunk. Panics with `IndexOutOfBounds` if either index is
+    /// not below `count`.
+    pub fn move_chunk(&self, from: u32, to: u32) {
+        let mut meta = self.meta();
+        if from >= meta.count || to >= meta.count {
+            panic_with_error!(self.env, ChonkError::IndexOutOfBounds);
+        }
+        if from == to {
+            return;
+        }
+        let first = core::cmp::min(from, to);
+        self.ensure_append_only(&meta, first);
+
+        match &mut meta.layout {
+            ChonkLayout::Dense => {
+                let key = |i| ChonkKey::Chunk(self.id.clone(), i);
+                let moved: Option<Bytes> = self.storage().get(&key(from));
+                let shift = |i: u32, j: u32| {
+                    if let Some(chunk) = self.storage().get::<Bytes>(&key(i)) {
+                        self.storage().set(&key(j), &chunk);
+                    }
+                };
+                if from < to {
+                    (from + 1..=to).for_each(|i| shift(i, i - 1));
+                } else {
+                    (to..from).rev().for_each(|i| shift(i, i + 1));
+                }
+                if let Some(chunk) = moved {
+                    self.storage().set(&key(to), &chunk);
+                }
+            }
+            ChonkLayout::Gapped(slots) => {
+                let slot = slots.map.get_unchecked(from);
+                slots.map.remove_unchecked(from);
+                slots.map.insert(to, slot);
+            }
+            ChonkLayout::Linked(_) => {
+                let moved = self.load_chunk(&meta, from).expect("linked chunk missing");
+                self.close_gap(&mut meta, from, from + 1);
+                self.insert_chunks(&mut meta, to, &Vec::from_array(self.env, [moved]))
+                    .unwrap_or_else(|e| panic_with_error!(self.env, e));
+            }
+        }
+
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("move"), first);
+    }
+
+    /// Reorder the chunks so that each compares as no greater than the next
+    /// under `cmp`, keeping equal chunks in their original order. Bumps the
+    /// version once if the order changed.
+    ///
+    /// Every chunk is read once and the order is found with a binary
+    /// insertion sort, so `cmp` runs O(n log n) times. Dense collections
+    /// then write each chunk that moved and linked ones rewrite each moved
+    /// node; gapped collections only rewrite the slot map in the metadata.
+    pub fn sort_by<F: FnMut(&Bytes, &Bytes) -> Ordering>(&self, mut cmp: F) {
+        let mut meta = self.meta();
+
+        // Chunks in sorted order, alongside the index each came from
+        let mut sorted: Vec<Bytes> = Vec::new(self.env);
+        let mut order: Vec<u32> = Vec::new(self.env);
+        for (index, chunk) in self.iter().enumerate() {
+            let (mut lo, mut hi) = (0, sorted.len());
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if cmp(&sorted.get_unchecked(mid), &chunk) == Ordering::Greater {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            sorted.insert(lo, chunk);
+            order.insert(lo, index as u32);
+        }
+
+        let Some(first) = (0..meta.count).find(|&i| order.get_unchecked(i) != i) else {
+            return;
+        };
+        self.ensure_append_only(&meta, first);
+
+        match &mut meta.layout {
+            ChonkLayout::Dense => {
+                for i in first..meta.count {
+                    if order.get_unchecked(i) != i {
+                        let key = ChonkKey::Chunk(self.id.clone(), i);
+                        self.storage()
+                            .set(&key, &self.encode(&sorted.get_unchecked(i)));
+                    }
+                }
+            }
+            ChonkLayout::Gapped(slots) => {
+                let mut map = Vec::new(self.env);
+                for from in order.iter() {
+                    map.push_back(slots.map.get_unchecked(from));
+                }
+                slots.map = map;
+            }
+            ChonkLayout::Linked(links) => {
+                let mut id = links.head;
+                for i in 0..meta.count {
+                    let node_id = id.expect("linked chunk missing");
+                    let mut node = self.load_node(node_id).expect("linked chunk missing");
+                    id = node.next;
+                    if order.get_unchecked(i) != i {
+                        node.data = self.encode(&sorted.get_unchecked(i));
+                        self.store_node(node_id, &node);
+                    }
+                }
+            }
+        }
+
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("sort"), first);
+    }
+
+    /// Stably reorder the chunks by ascending length. See `sort_by`.
+    pub fn sort_by_len(&self) {
+        self.sort_by(|a, b| a.len().cmp(&b.len()));
+    }
+
+    /// Remove trailing zero-length chunks, stopping at the last non-empty
+    /// one. Bumps the version once if anything was removed.
+    pub fn shrink_to_fit(&self) {
+        let mut meta = self.meta();
+        let empty = self
+            .iter()
+            .rev()
+            .take_while(|chunk| chunk.is_empty())
+            .count() as u32;
+        if empty == 0 {
+            return;
+        }
+
+        let count = meta.count;
+        let start = count - empty;
+        self.ensure_append_only(&meta, start);
+
+        self.close_gap(&mut meta, start, count);
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("remove"), start);
+    }
+
+    /// Remove each chunk equal to the one before it, like `Vec::dedup`.
+    /// Only the chunks from the first removed one onward are rewritten, with
+    /// one version bump; nothing is written if no neighbours are equal.
+    pub fn dedup_consecutive(&self) {
+        let mut meta = self.meta();
+        let mut first_removed = None;
+        let mut survivors: Vec<Bytes> = Vec::new(self.env);
+        let mut previous: Option<Bytes> = None;
+        for (index, chunk) in self.iter().enumerate() {
+            if previous.as_ref() == Some(&chunk) {
+                first_removed.get_or_insert(index as u32);
+                meta.total_bytes = sub_bytes(meta.total_bytes, chunk.len())
+                    .unwrap_or_else(|e| panic_with_error!(self.env, e));
+            } else {
+                // Chunks before the first duplicate stay where they are
+                if first_removed.is_some() {
+                    survivors.push_back(chunk.clone());
+                }
+                previous = Some(chunk);
+            }
+        }
+
+        let Some(first) = first_removed else {
+            return;
+        };
+        self.ensure_append_only(&meta, first);
+
+        let count = meta.count;
+        self.close_gap(&mut meta, first, count);
+        self.insert_chunks(&mut meta, first, &survivors)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("write"), first);
+    }
+
+    /// Remove the run of `byte` at the start of the content, dropping chunks
+    /// it covers (and empty chunks within it) and trimming the chunk where
+    /// it ends. Bumps the version once if anything was removed.
+    pub fn trim_start(&self, byte: u8) {
+        self.trim(byte, false);
+    }
+
+    /// Remove the run of `byte` at the end of the content, like
+    /// `trim_start`
+    pub fn trim_end(&self, byte: u8) {
+        self.trim(byte, true);
+    }
+
+    /// Shared body of `trim_start` and `trim_end`
+    fn trim(&self, byte: u8, from_end: bool) {
+        let mut meta = self.meta();
+        let count = meta.count;
+
+        // Count the chunks wholly inside the run, stopping at the first
+        // chunk that isn't
+        let mut covered = 0u32;
+        let mut removed = 0u64;
+        let mut edge = None;
+        let mut chunks = self.iter();
+        while let Some(chunk) = if from_end {
+            chunks.next_back()
+        } else {
+            chunks.next()
+        } {
+            if chunk.iter().all(|b| b == byte) {
+                covered += 1;
+                removed += chunk.len() as u64;
+            } else {
+                edge = Some(chunk);
+                break;
+            }
+        }
+        let (start, end) = if from_end {
+            (count - covered, count)
+        } else {
+            (0, covered)
+        };
+
+        // Cut the rest of the run off that chunk
+        let trimmed = edge.and_then(|chunk| {
+            let len = chunk.len();
+            let (index, run) = if from_end {
+                let run = (0..len)
+                    .rev()
+                    .take_while(|&i| chunk.get_unchecked(i) == byte)
+                    .count() as u32;
+                (start - 1, run)
+            } else {
+                (end, chunk.iter().take_while(|&b| b == byte).count() as u32)
+            };
+            let rest = if from_end {
+                chunk.slice(..len - run)
+            } else {
+                chunk.slice(run..)
+            };
+            (run > 0).then_some((index, rest, run))
+        });
+        if covered == 0 && trimmed.is_none() {
+            return;
+        }
+
+        let first = trimmed
+            .as_ref()
+            .map_or(start, |(index, _, _)| core::cmp::min(*index, start));
+        self.ensure_append_only(&meta, first);
+
+        if let Some((index, rest, run)) = trimmed {
+            removed += run as u64;
+            self.store_chunk(&meta, index, &rest);
+        }
+        meta.total_bytes = meta
+            .total_bytes
+            .checked_sub(removed)
+            .unwrap_or_else(|| panic_with_error!(self.env, ChonkError::StorageLimitExceeded));
+        self.close_gap(&mut meta, start, end);
+
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("trim"), first);
+    }
+
+    /// Remove all chunks
+    pub fn clear(&self) {
+        self.clear_counting();
+    }
+
+    /// Remove all chunks, returning how many were removed
+    pub fn clear_counting(&self) -> u32 {
+        let meta = self.meta();
+        self.ensure_append_only(&meta, 0);
+        if let Some(owner) = &meta.owner {
+            owner.require_auth();
+        }
+
+        // Remove all chunks
+        self.close_gap(&mut meta.clone(), 0, meta.count);
+
+        // Remove metadata
+        let meta_key = ChonkKey::Meta(self.id.clone());
+        self.storage().remove(&meta_key);
+        let count_key = ChonkKey::Count(self.id.clone());
+        self.storage().remove(&count_key);
+
+        // The version restarts, so drop the journal along with it
+        for v in journal_versions(&meta) {
+            self.storage().remove(&ChonkKey::Change(self.id.clone(), v));
+        }
+
+        if let Some(cache) = self.cache {
+            cache.set(&self.id, self.storage_kind, self.empty_meta());
+        }
+
+        self.emit(&meta, symbol_short!("clear"), 0);
+        meta.count
+    }
+
+    /// Remove up to `max_removals` chunks from the end, returning `true`
+    /// once the collection is fully cleared.
+    ///
+    /// Bounds the work done per call so a collection too large to `clear`
+    /// in one transaction can be cleared across several. The call that
+    /// removes the last chunks also removes the metadata, like `clear`.
+    pub fn clear_partial(&self, max_removals: u32) -> bool {
+        let mut meta = self.meta();
+        let start = meta.count.saturating_sub(max_removals);
+        if start == 0 {
+            self.clear();
+            return true;
+        }
+        if start == meta.count {
+            return false;
+        }
+        self.ensure_append_only(&meta, start);
+
+        for i in start..meta.count {
+            if let Some(chunk) = self.load_chunk(&meta, i) {
+                meta.total_bytes = sub_bytes(meta.total_bytes, chunk.len())
+                    .unwrap_or_else(|e| panic_with_error!(self.env, e));
+            }
+        }
+
+        let count = meta.count;
+        self.close_gap(&mut meta, start, count);
+        meta.version =
+            bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(self.env, e));
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("remove"), start);
+        false
+    }
+
+    /// Exchange the contents of two collections, so readers of `id_a` see
+    /// what was stored under `id_b` and vice versa.
+    ///
+    /// Chunks are swapped pairwise by key, which needs no scratch id and
+    /// so cannot collide with an existing collection. Layouts travel with
+    /// the metadata, so dense and gapped collections may be swapped freely.
+    ///
+    /// Both collections must live in `kind` storage. Panics with
+    /// `AppendOnly` if either collection is append-only and not empty, and
+    /// requires the authorization of each collection's owner.
+    pub fn swap_collections(env: &Env, id_a: Symbol, id_b: Symbol, kind: StorageKind) {
+        if id_a == id_b {
+            return;
+        }
+
+        let store = Store::new(env, kind);
+        let meta_a_key = ChonkKey::Meta(id_a.clone());
+        let meta_b_key = ChonkKey::Meta(id_b.clone());
+        let meta_a = load_meta(&store, &meta_a_key);
+        let meta_b = load_meta(&store, &meta_b_key);
+        for meta in meta_a.iter().chain(meta_b.iter()) {
+            if meta.append_only && meta.count > 0 {
+                panic_with_error!(env, ChonkError::AppendOnly);
+            }
+        }
+        for owner in meta_a
+            .iter()
+            .chain(meta_b.iter())
+            .filter_map(|m| m.owner.as_ref())
+        {
+            owner.require_auth();
+        }
+
+        let (chunks_a, slots_a, nodes_a, changes_a) = key_extent(meta_a.as_ref());
+        let (chunks_b, slots_b, nodes_b, changes_b) = key_extent(meta_b.as_ref());
+
+        for i in 0..core::cmp::max(chunks_a, chunks_b) {
+            let key_a = ChonkKey::Chunk(id_a.clone(), i);
+            let key_b = ChonkKey::Chunk(id_b.clone(), i);
+            swap_entries::<Bytes>(&store, &key_a, &key_b);
+        }
+        for i in 0..core::cmp::max(slots_a, slots_b) {
+            let key_a = ChonkKey::Slot(id_a.clone(), i);
+            let key_b = ChonkKey::Slot(id_b.clone(), i);
+            swap_entries::<Bytes>(&store, &key_a, &key_b);
+        }
+        for i in 0..core::cmp::max(nodes_a, nodes_b) {
+            let key_a = ChonkKey::Node(id_a.clone(), i);
+            let key_b = ChonkKey::Node(id_b.clone(), i);
+            swap_entries::<ChonkNode>(&store, &key_a, &key_b);
+        }
+        let changes = changes_a
+            .clone()
+            .chain(changes_b.filter(|v| !changes_a.contains(v)));
+        for v in changes {
+            let key_a = ChonkKey::Change(id_a.clone(), v);
+            let key_b = ChonkKey::Change(id_b.clone(), v);
+            swap_entries::<u32>(&store, &key_a, &key_b);
+        }
+
+        put_or_remove(&store, &meta_a_key, meta_b.as_ref());
+        put_or_remove(&store, &meta_b_key, meta_a.as_ref());
+
+        let count_a_key = ChonkKey::Count(id_a);
+        let count_b_key = ChonkKey::Count(id_b);
+        swap_entries::<u32>(&store, &count_a_key, &count_b_key);
+    }
+
+    /// Move the collection's entries (chunks, metadata and journal) to `to`
+    /// storage, e.g. to promote temporary data to persistent, and return a
+    /// handle on the new location. This handle keeps addressing the old
+    /// storage, which is left empty.
+    ///
+    /// Content and version are unchanged. Panics if a collection with the
+    /// same id already exists in `to` storage.
+    pub fn migrate_storage(&self, to: StorageKind) -> Chonk<'a> {
+        let moved = Chonk {
+            storage_kind: to,
+            ..self.clone()
+        };
+        if to == self.storage_kind {
+            return moved;
+        }
+
+        let (from_store, to_store) = (self.storage(), moved.storage());
+        let meta_key = ChonkKey::Meta(self.id.clone());
+        let meta = load_meta(&from_store, &meta_key);
+        if let Some(owner) = meta.as_ref().and_then(|meta| meta.owner.as_ref()) {
+            owner.require_auth();
+        }
+        if to_store.has(&meta_key) {
+            panic!("Destination must be empty");
+        }
+
+        let (chunks, slots, nodes, changes) = key_extent(meta.as_ref());
+        for i in 0..chunks {
+            move_entry::<Bytes>(&from_store, &to_store, &ChonkKey::Chunk(self.id.clone(), i));
+        }
+        for i in 0..slots {
+            move_entry::<Bytes>(&from_store, &to_store, &ChonkKey::Slot(self.id.clone(), i));
+        }
+        for i in 0..nodes {
+            move_entry::<ChonkNode>(&from_store, &to_store, &ChonkKey::Node(self.id.clone(), i));
+        }
+        for v in changes {
+            move_entry::<u32>(
+                &from_store,
+                &to_store,
+                &ChonkKey::Change(self.id.clone(), v),
+            );
+        }
+
+        put_or_remove(&to_store, &meta_key, meta.as_ref());
+        from_store.remove(&meta_key);
+        move_entry::<u32>(&from_store, &to_store, &ChonkKey::Count(self.id.clone()));
+
+        // The old location is now empty
+        if let Some(cache) = self.cache {
+            cache.invalidate(&self.id, self.storage_kind);
+        }
+        moved
+    }
+
+    // ─── Lifetime ──────────────────────────────────────────
+
+    /// Extend the TTL of chunks `[start, start + count)`, clamped to the
+    /// collection's count, to `extend_to` ledgers wherever it has fallen
+    /// below `threshold`. The window starting at 0 also covers the metadata
+    /// and count entries.
+    ///
+    /// Returns the index the next window should start from; once it reaches
+    /// `count()` the whole collection has been covered. Lets a collection
+    /// too large to extend in one transaction be extended across several.
+    pub fn extend_ttl_partial(
+        &self,
+        start: u32,
+        count: u32,
+        threshold: u32,
+        extend_to: u32,
+    ) -> u32 {
+        let meta = self.meta();
+        let end = core::cmp::min(start.saturating_add(count), meta.count);
+        let store = self.storage();
+
+        if start == 0 {
+            for key in [
+                ChonkKey::Meta(self.id.clone()),
+                ChonkKey::Count(self.id.clone()),
+            ] {
+                if store.has(&key) {
+                    store.extend_ttl(&key, threshold, extend_to);
+                }
+            }
+        }
+        if start >= end {
+            return end;
+        }
+
+        match &meta.layout {
+            ChonkLayout::Dense => {
+                for i in start..end {
+                    store.extend_ttl(&ChonkKey::Chunk(self.id.clone(), i), threshold, extend_to);
+                }
+            }
+            ChonkLayout::Gapped(slots) => {
+                for i in start..end {
+                    let key = ChonkKey::Slot(self.id.clone(), slots.map.get_unchecked(i));
+                    store.extend_ttl(&key, threshold, extend_to);
+                }
+            }
+            ChonkLayout::Linked(links) => {
+                // Walk to the window once, then follow the links
+                let mut id = Some(self.node_at(links, meta.count, start));
+                for _ in start..end {
+                    let node = id.expect("linked chunk missing");
+                    store.extend_ttl(&ChonkKey::Node(self.id.clone(), node), threshold, extend_to);
+                    id = self.load_node(node).and_then(|n| n.next);
+                }
+            }
+        }
+        end
+    }
+
+    // ─── Bulk Operations ───────────────────────────────────
+
+    /// Write content, automatically chunking at specified size
+    ///
+    /// Existing content is replaced. Chunk keys are written directly and the
+    /// metadata is saved once, so the version advances by exactly one.
+    ///
+    /// A `chunk_size` at or above the content length is fine and stores the
+    /// content as a single chunk. Empty content leaves the collection empty.
+    /// Panics if `chunk_size` is zero, with `AppendOnly` if the collection
+    /// is append-only and not empty, or with `ChunkTooLarge` if a chunk
+    /// breaks the handle's `max_chunk_size`.
+    ///
+    /// Returns the number of chunks written; they occupy indices `0..n`.
+    pub fn write_chunked(&self, content: Bytes, chunk_size: u32) -> u32 {
+        if chunk_size == 0 {
+            panic!("Chunk size must be non-zero");
+        }
+
+        self.rewrite(content, chunk_size)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Write a raw byte slice like `write_chunked`, copying each chunk out of
+    /// `data` directly instead of building the full content as `Bytes`
+    /// first.
+    ///
+    /// Fails with `InvalidChunkSize` if `chunk_size` is zero, with
+    /// `AppendOnly` if the collection is append-only and not empty, or with
+    /// `ChunkTooLarge` if a chunk breaks the handle's `max_chunk_size`.
+    pub fn write_slice_chunked(&self, data: &[u8], chunk_size: u32) -> Result<u32, ChonkError> {
+        if chunk_size == 0 {
+            return Err(ChonkError::InvalidChunkSize);
+        }
+        let meta = self.meta();
+        self.check_append_only(&meta, 0)?;
+
+        let mut pieces = Vec::new(self.env);
+        for piece in data.chunks(chunk_size as usize) {
+            pieces.push_back(Bytes::from_slice(self.env, piece));
+        }
+        self.write_pieces(meta, &pieces)
+    }
+
+    /// Replace the content like `write_chunked`, but only if the collection
+    /// is still at `expected_version`; otherwise fail with `VersionConflict`
+    /// and leave it untouched. A zero `chunk_size` fails with
+    /// `InvalidChunkSize`, and `AppendOnly` and `ChunkTooLarge` are returned
+    /// instead of panicking.
+    pub fn replace_if_version(
+        &self,
+        content: Bytes,
+        chunk_size: u32,
+        expected_version: u64,
+    ) -> Result<(), ChonkError> {
+        if chunk_size == 0 {
+            return Err(ChonkError::InvalidChunkSize);
+        }
+        let meta = self.meta();
+        if meta.version != expected_version {
+            return Err(ChonkError::VersionConflict);
+        }
+
+        self.rewrite(content, chunk_size).map(|_| ())
+    }
+
+    /// Transform every chunk through `f`, writing the results as the
+    /// corresponding chunks of collection `dest_id`.
+    ///
+    /// The destination is cleared first and recreated in this handle's
+    /// layout. Mapping a collection onto its own id rewrites it in place.
+    pub fn map_collect(&self, dest_id: Symbol, f: impl Fn(Bytes) -> Bytes) {
+        let dest = self.sibling(dest_id);
+        dest.ensure_append_only(&dest.meta(), 0);
+
+        let mut mapped = Vec::new(self.env);
+        for chunk in self.iter() {
+            mapped.push_back(f(chunk));
+        }
+
+        if dest.id != self.id {
+            dest.clear();
+        }
+        dest.write_pieces(dest.meta(), &mapped)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Copy the content into the empty collection `dest_id` as uniform
+    /// `chunk_size` chunks (the last may be shorter), leaving this one
+    /// untouched. Returns the number of chunks written.
+    ///
+    /// The destination is created in this handle's layout. Panics if
+    /// `chunk_size` is zero or the destination holds any chunks.
+    pub fn compact_into(&self, dest_id: Symbol, chunk_size: u32) -> u32 {
+        if chunk_size == 0 {
+            panic!("Chunk size must be non-zero");
+        }
+        let dest = self.sibling(dest_id);
+        let meta = dest.meta();
+        if meta.count > 0 {
+            panic!("Destination must be empty");
+        }
+
+        let mut pieces = Vec::new(self.env);
+        for page in self.pages(chunk_size) {
+            pieces.push_back(page);
+        }
+
+        dest.write_pieces(meta, &pieces)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e))
+    }
+
+    /// Copy chunks `[start, start + count)`, clamped to this collection's
+    /// chunks, into the empty collection `dest_id`, leaving this one
+    /// untouched. The copy starts at version 0, as if freshly created.
+    /// Returns the number of chunks copied.
+    ///
+    /// The destination is created in this handle's layout. Panics if it
+    /// holds any chunks.
+    pub fn clone_range_into(&self, dest_id: Symbol, start: u32, count: u32) -> u32 {
+        let dest = self.sibling(dest_id);
+        let meta = dest.meta();
+        if meta.count > 0 {
+            panic!("Destination must be empty");
+        }
+
+        let chunks = self.get_range(start, count);
+        let mut meta = dest
+            .replace_contents(meta, &chunks)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+        meta.version = 0;
+        dest.save_meta(&meta);
+        dest.emit(&meta, symbol_short!("write"), 0);
+        meta.count
+    }
+
+    /// Merge the chunks of `a` and `b`, each already sorted under `cmp`, into
+    /// collection `dest_id` so that it is sorted too. On ties chunks of `a`
+    /// come first.
+    ///
+    /// Each input is read once, in order. The destination's contents are
+    /// replaced; it is created in `a`'s layout and may be `a` or `b`
+    /// itself. Panics with `AppendOnly` if it is append-only and not empty.
+    ///
+    /// A separate destination is written chunk by chunk as the merge goes,
+    /// so only the next chunk of each input is held in memory. If the
+    /// destination is `a` or `b`, the whole merge is buffered first, since
+    /// writing early would overwrite chunks not yet read.
+    pub fn merge_sorted_into<F: FnMut(&Bytes, &Bytes) -> Ordering>(
+        a: &Chonk<'a>,
+        b: &Chonk<'a>,
+        dest_id: Symbol,
+        mut cmp: F,
+    ) {
+        let dest = a.sibling(dest_id);
+        let meta = dest.meta();
+        dest.ensure_append_only(&meta, 0);
+
+        let (mut left, mut right) = (a.iter().peekable(), b.iter().peekable());
+        let mut next = || {
+            let take_left = match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) => cmp(l, r) != Ordering::Greater,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return None,
+            };
+            if take_left { left.next() } else { right.next() }
+        };
+
+        let aliased = dest.id == a.id || (dest.id == b.id && dest.storage_kind == b.storage_kind);
+        if aliased {
+            let mut merged = Vec::new(a.env);
+            while let Some(chunk) = next() {
+                merged.push_back(chunk);
+            }
+            dest.write_pieces(meta, &merged)
+                .unwrap_or_else(|e| panic_with_error!(a.env, e));
+            return;
+        }
+
+        let version = bump_version(meta.version).unwrap_or_else(|e| panic_with_error!(a.env, e));
+        let mut meta = dest
+            .replace_contents(meta, &Vec::new(a.env))
+            .unwrap_or_else(|e| panic_with_error!(a.env, e));
+        while let Some(chunk) = next() {
+            dest.check_chunk_len(&chunk)
+                .unwrap_or_else(|e| panic_with_error!(a.env, e));
+            meta.total_bytes = add_bytes(meta.total_bytes, chunk.len())
+                .unwrap_or_else(|e| panic_with_error!(a.env, e));
+            let end = meta.count;
+            dest.insert_chunks(&mut meta, end, &Vec::from_array(a.env, [chunk]))
+                .unwrap_or_else(|e| panic_with_error!(a.env, e));
+        }
+        meta.version = version;
+        dest.save_meta(&meta);
+        dest.publish(&meta, symbol_short!("write"), 0);
+    }
+
+    /// Replace every non-overlapping occurrence of `pattern` with
+    /// `replacement`, re-chunking the result.
+    ///
+    /// Chunks are cut at the collection's recorded `chunk_size`, else the
+    /// configured `max_chunk_size`, else the largest existing chunk length.
+    /// Does nothing if the pattern doesn't occur. Panics if `pattern` is
+    /// empty.
+    pub fn replace_all(&self, pattern: &Bytes, replacement: &Bytes) {
+        let content = self.assemble();
+        let mut result = Bytes::new(self.env);
+        let mut copied = 0u32;
+        let mut matched = false;
+        find_matches(&content, pattern, |i| {
+            result.append(&content.slice(copied..i));
+            result.append(replacement);
+            copied = i + pattern.len();
+            matched = true;
+        });
+
+        if !matched {
+            return;
+        }
+        result.append(&content.slice(copied..));
+
+        let chunk_size = self
+            .meta()
+            .chunk_size
+            .or(self.max_chunk_size)
+            .unwrap_or_else(|| self.lengths().max().unwrap_or(0));
+        self.rewrite(result, core::cmp::max(chunk_size, 1))
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Overwrite the collection with `content` cut into `chunk_size` pieces,
+    /// removing stale trailing chunks and saving the metadata once. Returns
+    /// the new chunk count. Nothing is written on error.
+    pub(crate) fn rewrite(&self, content: Bytes, chunk_size: u32) -> Result<u32, ChonkError> {
+        let meta = self.meta();
+        self.check_append_only(&meta, 0)?;
+
+        let mut pieces = Vec::new(self.env);
+        let mut offset = 0u32;
+        while offset < content.len() {
+            let end = core::cmp::min(offset + chunk_size, content.len());
+            pieces.push_back(content.slice(offset..end));
+            offset = end;
+        }
+
+        self.write_pieces(meta, &pieces)
+    }
+
+    /// Replace the contents with `pieces` as one write, saving the metadata
+    /// once. Returns the new chunk count. Nothing is written on error.
+    fn write_pieces(&self, meta: ChonkMeta, pieces: &Vec<Bytes>) -> Result<u32, ChonkError> {
+        let version = bump_version(meta.version)?;
+        let mut meta = self.replace_contents(meta, pieces)?;
+        meta.version = version;
+        self.save_meta(&meta);
+        self.publish(&meta, symbol_short!("write"), 0);
+        Ok(meta.count)
+    }
+
+    /// Move every chunk of `other` onto the end of this collection, then
+    /// clear `other`. Suits a build-then-commit flow: fill a staging
+    /// collection across calls, then append it in one step.
+    ///
+    /// The chunks are added like `insert_many` at the end, with one version
+    /// bump. Panics if `other` is this collection, or with `AppendOnly`
+    /// (before anything is written) if `other` is append-only and not empty.
+    pub fn append_collection(&self, other: &Chonk) {
+        if other.id == self.id && other.storage_kind == self.storage_kind {
+            panic!("Cannot append a collection to itself");
+        }
+        let other_meta = other.meta();
+        other.ensure_append_only(&other_meta, 0);
+
+        self.insert_many(self.count(), other.to_vec());
+        other.clear();
+    }
+
+    /// Append content to last chunk or create new if it would exceed max size
+    ///
+    /// Panics with `InvalidChunkSize` if the collection has a stored chunk
+    /// size other than `max_chunk_size`.
+    pub fn append(&self, content: Bytes, max_chunk_size: u32) {
+        self.try_append(content, max_chunk_size)
+            .unwrap_or_else(|e| panic_with_error!(self.env, e));
+    }
+
+    /// Append content like `append`, returning an error instead of panicking
+    pub fn try_append(&self, content: Bytes, max_chunk_size: u32) -> Result<(), ChonkError> {
+        let meta = self.meta();
+        if meta.chunk_size.is_some_and(|size| size != max_chunk_size) {
+            return Err(ChonkError::InvalidChunkSize);
+        }
+
+        if meta.count == 0 {
+            self.try_push(content)?;
+            return Ok(());
+        }
+
+        let last_index = meta.count - 1;
+        if let Some(last_chunk) = self.get(last_index) {
+            let new_len = last_chunk.len() + content.len();
+            if new_len <= max_chunk_size {
+                // Append to existing chunk
+                let mut combined = Bytes::new(self.env);
+                combined.append(&last_chunk);
+                combined.append(&content);
+
+                // Only extends the last chunk, so append-only collections allow it
+                self.overwrite(meta, last_index, combined)?;
+            } else {
+                // Create new chunk
+                self.try_push(content)?;
+            }
+        } else {
+            self.try_push(content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `value` under `key`, or remove the key if there is no value
+fn put_or_remove<V>(store: &Store, key: &ChonkKey, value: Option<&V>)
+where
+    V: IntoVal<Env, Val>,
+{
+    match value {
+        Some(v) => store.set(key, v),
+        None => store.remove(key),
+    }
+}
+
+/// Exchange the values stored under two keys, removing a key whose
+/// counterpart is absent
+fn swap_entries<V>(store: &Store, key_a: &ChonkKey, key_b: &ChonkKey)
+where
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let value_a: Option<V> = store.get(key_a);
+    let value_b: Option<V> = store.get(key_b);
+    put_or_remove(store, key_a, value_b.as_ref());
+    put_or_remove(store, key_b, value_a.as_ref());
+}
+
+/// Move the value stored under `key` from one store to another, if any
+fn move_entry<V>(from: &Store, to: &Store, key: &ChonkKey)
+where
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    if let Some(value) = from.get::<V>(key) {
+        to.set(key, &value);
+        from.remove(key);
+    }
+}
+
+/// Upper bounds of the chunk, slot and node ids, and the journal versions,
+/// a collection may occupy
+fn key_extent(meta: Option<&ChonkMeta>) -> (u32, u32, u32, Range<u64>) {
+    let Some(meta) = meta else {
+        return (0, 0, 0, 0..0);
+    };
+    let changes = journal_versions(meta);
+    match &meta.layout {
+        ChonkLayout::Dense => (meta.count, 0, 0, changes),
+        ChonkLayout::Gapped(slots) => (0, slots.next, 0, changes),
+        ChonkLayout::Linked(links) => (0, 0, links.next, changes),
+    }
+}
+
+/// Versions whose journal records a collection may still hold: the last
+/// `JOURNAL_WINDOW`, or none if it isn't journaled
+fn journal_versions(meta: &ChonkMeta) -> Range<u64> {
+    if !meta.journal {
+        return 0..0;
+    }
+    meta.version.saturating_sub(JOURNAL_WINDOW) + 1..meta.version.saturating_add(1)
+}
+
+/// Report each non-overlapping occurrence of `pattern` in `haystack`,
+/// scanning left to right. Returns the first position not yet ruled out,
+/// from which a match could still begin given more bytes.
+fn find_matches(haystack: &Bytes, pattern: &Bytes, mut on_match: impl FnMut(u32)) -> u32 {
+    if pattern.is_empty() {
+        panic!("Pattern must not be empty");
+    }
+
+    let pattern_len = pattern.len();
+    let first = pattern.get(0);
+    let mut i = 0;
+    while i + pattern_len <= haystack.len() {
+        if haystack.get(i) == first && haystack.slice(i..i + pattern_len) == *pattern {
+            on_match(i);
+            i += pattern_len;
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// `len` copies of `byte`, extended from a fixed block rather than
+/// pushed byte by byte
+fn repeat_byte(env: &Env, byte: u8, len: u32) -> Bytes {
+    let block = [byte; 64];
+    let mut data = Bytes::new(env);
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = core::cmp::min(remaining, block.len() as u32);
+        data.extend_from_slice(&block[..n as usize]);
+        remaining -= n;
+    }
+    data
+}
+
+/// CRC-32 with the IEEE polynomial, as used by zlib and PNG
+fn crc32(data: &Bytes) -> u32 {
+    let mut crc = !0u32;
+    for byte in data.iter() {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Load metadata stored under `key`, upgrading entries written in the
+/// 1.0 layout (32-bit `total_bytes` and `version`). Upgraded metadata is
+/// stored in the current layout on the next write.
+fn load_meta(store: &Store, key: &ChonkKey) -> Option<ChonkMeta> {
+    let env = store.env;
+    let raw: Map<Symbol, Val> = store.get(key)?;
+
+    // Decoding a struct from a map with a different field count is a host
+    // error rather than an `Err`, so tell the layouts apart up front
+    let decoded = if raw.contains_key(symbol_short!("layout")) {
+        ChonkMeta::try_from_val(env, &raw.to_val())
+    } else {
+        ChonkMetaV1::try_from_val(env, &raw.to_val()).map(ChonkMeta::from)
+    };
+    Some(decoded.unwrap_or_else(|_| panic!("Unrecognized metadata layout")))
+}
+
+/// Add a chunk length to a byte total, reporting overflow as `StorageLimitExceeded`
+fn add_bytes(total: u64, len: u32) -> Result<u64, ChonkError> {
+    total
+        .checked_add(len as u64)
+        .ok_or(ChonkError::StorageLimitExceeded)
+}
+
+/// Subtract a chunk length from a byte total, reporting underflow as `StorageLimitExceeded`
+fn sub_bytes(total: u64, len: u32) -> Result<u64, ChonkError> {
+    total
+        .checked_sub(len as u64)
+        .ok_or(ChonkError::StorageLimitExceeded)
+}
+
+/// Whether `meta` holds a digest computed at its current version
+fn hash_is_current(meta: &ChonkMeta) -> bool {
+    meta.content_hash.is_some() && meta.hash_version == meta.version
+}
+
+/// Advance a version, reporting overflow as `StorageLimitExceeded`
+fn bump_version(version: u64) -> Result<u64, ChonkError> {
+    version
+        .checked_add(1)
+        .ok_or(ChonkError::StorageLimitExceeded)
+}
+
+/// Add with overflow reported as `StorageLimitExceeded`
+fn checked_add(a: u32, b: u32) -> Result<u32, ChonkError> {
+    a.checked_add(b).ok_or(ChonkError::StorageLimitExceeded)
 }
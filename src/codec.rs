@@ -0,0 +1,79 @@
+use soroban_sdk::Bytes;
+
+/// Transforms chunk data on its way to and from storage, e.g. to compress it
+///
+/// A handle opened with [`Chonk::open_with_codec`](crate::Chonk::open_with_codec)
+/// encodes every chunk it writes and decodes every chunk it reads, so the
+/// rest of the API, including lengths, `total_bytes` and checksums, works on
+/// the decoded bytes. Every handle on a collection must use the same codec.
+pub trait ChonkCodec {
+    /// Encode a chunk for storage
+    fn encode(&self, data: &Bytes) -> Bytes;
+
+    /// Decode a chunk produced by `encode`
+    fn decode(&self, data: &Bytes) -> Bytes;
+}
+
+/// Codec that stores chunks unchanged
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopCodec;
+
+impl ChonkCodec for NoopCodec {
+    fn encode(&self, data: &Bytes) -> Bytes {
+        data.clone()
+    }
+
+    fn decode(&self, data: &Bytes) -> Bytes {
+        data.clone()
+    }
+}
+
+/// Run-length codec storing each run of a byte as `(length, byte)`, with
+/// runs of at most 255
+///
+/// Shrinks data with long runs, such as zero-filled buffers, and doubles
+/// data without them. Decoding panics on input of odd length or with a
+/// zero-length run.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RleCodec;
+
+impl ChonkCodec for RleCodec {
+    fn encode(&self, data: &Bytes) -> Bytes {
+        let mut out = Bytes::new(data.env());
+        let mut run: Option<(u8, u8)> = None;
+        for byte in data.iter() {
+            run = match run {
+                Some((len, current)) if current == byte && len < u8::MAX => Some((len + 1, byte)),
+                Some((len, current)) => {
+                    out.extend_from_slice(&[len, current]);
+                    Some((1, byte))
+                }
+                None => Some((1, byte)),
+            };
+        }
+        if let Some((len, current)) = run {
+            out.extend_from_slice(&[len, current]);
+        }
+        out
+    }
+
+    fn decode(&self, data: &Bytes) -> Bytes {
+        if !data.len().is_multiple_of(2) {
+            panic!("Invalid RLE data");
+        }
+
+        let mut out = Bytes::new(data.env());
+        let mut pair = [0u8; 2];
+        for i in (0..data.len()).step_by(2) {
+            data.slice(i..i + 2).copy_into_slice(&mut pair);
+            let [len, byte] = pair;
+            if len == 0 {
+                panic!("Invalid RLE data");
+            }
+            for _ in 0..len {
+                out.push_back(byte);
+            }
+        }
+        out
+    }
+}
@@ -1,5 +1,9 @@
 use soroban_sdk::contracterror;
 
+/// Errors returned by collection operations
+///
+/// The discriminants are what contracts and off-chain clients see, so they
+/// never change: new variants take the next free number.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -12,4 +16,18 @@ pub enum ChonkError {
     ChunkTooLarge = 3,
     /// Operation would exceed storage limits
     StorageLimitExceeded = 4,
+    /// Collection is append-only
+    AppendOnly = 5,
+    /// Collection changed since the expected version
+    VersionConflict = 6,
+    /// Input is not in the expected encoding
+    InvalidFormat = 7,
+    /// Chunk size conflicts with the collection's stored chunk size
+    InvalidChunkSize = 8,
+    /// Stored content doesn't match its expected checksum
+    IntegrityMismatch = 9,
+    /// An argument is outside the values the operation accepts
+    InvalidArgument = 10,
+    /// Stored entries disagree with the collection's metadata
+    Corrupted = 11,
 }
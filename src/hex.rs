@@ -0,0 +1,65 @@
+use crate::chonk::Chonk;
+use crate::error::ChonkError;
+use soroban_sdk::{Bytes, Env};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+impl<'a> Chonk<'a> {
+    /// Lowercase hex encoding of the assembled content.
+    ///
+    /// Chunks are encoded as they are read, so only the output is held in
+    /// memory alongside one chunk.
+    pub fn assemble_hex(&self) -> Bytes {
+        let mut out = Bytes::new(self.env);
+        for chunk in self.iter() {
+            for byte in chunk.iter() {
+                out.extend_from_slice(&[
+                    DIGITS[(byte >> 4) as usize],
+                    DIGITS[(byte & 0x0f) as usize],
+                ]);
+            }
+        }
+        out
+    }
+
+    /// Decode hex (either case) and store the result like `write_chunked`,
+    /// replacing the existing content.
+    ///
+    /// Fails with `InvalidChunkSize` if `chunk_size` is zero, with
+    /// `InvalidFormat` if `hex` has odd length or a non-hex digit, or like
+    /// `write_slice_chunked` otherwise, leaving the collection untouched.
+    pub fn import_hex(&self, hex: Bytes, chunk_size: u32) -> Result<(), ChonkError> {
+        if chunk_size == 0 {
+            return Err(ChonkError::InvalidChunkSize);
+        }
+        let content = decode(self.env, &hex)?;
+        self.rewrite(content, chunk_size).map(|_| ())
+    }
+}
+
+/// Decode a string of hex digit pairs
+fn decode(env: &Env, hex: &Bytes) -> Result<Bytes, ChonkError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(ChonkError::InvalidFormat);
+    }
+
+    let mut out = Bytes::new(env);
+    let mut pair = [0u8; 2];
+    for i in (0..hex.len()).step_by(2) {
+        hex.slice(i..i + 2).copy_into_slice(&mut pair);
+        let high = nibble(pair[0]).ok_or(ChonkError::InvalidFormat)?;
+        let low = nibble(pair[1]).ok_or(ChonkError::InvalidFormat)?;
+        out.push_back(high << 4 | low);
+    }
+    Ok(out)
+}
+
+/// Value of a hex digit
+fn nibble(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
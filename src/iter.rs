@@ -1,21 +1,44 @@
-use crate::types::ChonkKey;
+use crate::chonk::Chonk;
+use crate::types::{ChonkLayout, ChonkMeta};
 use soroban_sdk::{Bytes, Env, Symbol};
 
 /// Iterator over chunks in a Chonk collection
 pub struct ChonkIter<'a> {
-    env: &'a Env,
-    id: Symbol,
+    chonk: Chonk<'a>,
+    meta: ChonkMeta,
     count: u32,
     current: u32,
+    /// Nodes of the next chunk from each end, for linked collections
+    front: Option<u32>,
+    back: Option<u32>,
 }
 
 impl<'a> ChonkIter<'a> {
+    /// Iterate over the first `count` chunks of a dense collection
     pub fn new(env: &'a Env, id: Symbol, count: u32) -> Self {
+        let meta = ChonkMeta {
+            count,
+            ..ChonkMeta::new()
+        };
+        Self::over(Chonk::open(env, id), meta, count)
+    }
+
+    /// Iterate over the first `count` chunks, resolving keys through `meta`
+    pub(crate) fn over(chonk: Chonk<'a>, meta: ChonkMeta, count: u32) -> Self {
+        let (front, back) = match &meta.layout {
+            ChonkLayout::Linked(links) if count > 0 => (
+                links.head,
+                Some(chonk.node_at(links, meta.count, count - 1)),
+            ),
+            _ => (None, None),
+        };
         Self {
-            env,
-            id,
+            chonk,
+            meta,
             count,
             current: 0,
+            front,
+            back,
         }
     }
 }
@@ -28,13 +51,37 @@ impl<'a> Iterator for ChonkIter<'a> {
             return None;
         }
 
-        let key = ChonkKey::Chunk(self.id.clone(), self.current);
-        let result = self.env.storage().persistent().get(&key);
+        let result = match self.meta.layout {
+            ChonkLayout::Linked(_) => {
+                let node = self.front.and_then(|id| self.chonk.load_node(id));
+                self.front = node.as_ref().and_then(|node| node.next);
+                node.map(|node| self.chonk.decode(node.data))
+            }
+            _ => self.chonk.load_chunk(&self.meta, self.current),
+        };
         self.current += 1;
         result
     }
 }
 
+impl<'a> DoubleEndedIterator for ChonkIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current >= self.count {
+            return None;
+        }
+
+        self.count -= 1;
+        match self.meta.layout {
+            ChonkLayout::Linked(_) => {
+                let node = self.back.and_then(|id| self.chonk.load_node(id));
+                self.back = node.as_ref().and_then(|node| node.prev);
+                node.map(|node| self.chonk.decode(node.data))
+            }
+            _ => self.chonk.load_chunk(&self.meta, self.count),
+        }
+    }
+}
+
 impl<'a> ExactSizeIterator for ChonkIter<'a> {
     fn len(&self) -> usize {
         (self.count - self.current) as usize
@@ -1,26 +1,46 @@
 #![no_std]
 
+#[cfg(feature = "base64")]
+mod base64;
+mod blob;
+mod builder;
+mod cache;
 mod chonk;
+mod codec;
 mod error;
+#[cfg(feature = "hex")]
+mod hex;
 mod iter;
+mod slice;
+mod storage;
 mod types;
+mod view;
 
-pub use chonk::Chonk;
+pub use blob::ChonkBlob;
+pub use builder::ChonkBuilder;
+pub use cache::ChonkCache;
+pub use chonk::{Chonk, ENTRY_OVERHEAD_BYTES, JOURNAL_WINDOW};
+pub use codec::{ChonkCodec, NoopCodec, RleCodec};
 pub use error::ChonkError;
 pub use iter::ChonkIter;
-pub use types::{ChonkKey, ChonkMeta};
+pub use slice::ChonkSlice;
+pub use storage::StorageKind;
+pub use types::{
+    ChonkKey, ChonkLayout, ChonkLinks, ChonkMeta, ChonkNode, ChonkSlots, chunk_key, meta_key,
+};
+pub use view::ChonkView;
 
 /// Prelude for convenient imports
 pub mod prelude {
-    pub use crate::{Chonk, ChonkError, ChonkIter, ChonkKey, ChonkMeta};
+    pub use crate::{
+        Chonk, ChonkBlob, ChonkCache, ChonkCodec, ChonkError, ChonkIter, ChonkKey, ChonkMeta,
+        ChonkSlice, ChonkView, StorageKind,
+    };
 }
 
 #[cfg(test)]
-mod tests {
-    extern crate std;
-
-    use super::*;
-    use soroban_sdk::{Address, Bytes, Env, contract, contractimpl, symbol_short};
+mod test_support {
+    use soroban_sdk::{Address, Env, contract, contractimpl};
 
     // Test contract to provide contract context for storage access
     #[contract]
@@ -31,9 +51,18 @@ mod tests {
         pub fn init(_env: Env) {}
     }
 
-    fn test_contract_id(env: &Env) -> Address {
+    pub fn test_contract_id(env: &Env) -> Address {
         env.register(TestContract, ())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::test_support::test_contract_id;
+    use soroban_sdk::{Bytes, Env, symbol_short};
 
     #[test]
     fn test_empty_chonk() {
@@ -113,6 +142,41 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_write_chunked_edge_cases() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            // Chunk size larger than the content yields a single chunk
+            let content = Bytes::from_slice(&env, b"ABC");
+            chonk.write_chunked(content.clone(), 100);
+            assert_eq!(chonk.count(), 1);
+            assert_eq!(chonk.get(0), Some(content));
+            assert_eq!(chonk.total_bytes(), 3);
+
+            // Empty content clears the collection
+            chonk.write_chunked(Bytes::new(&env), 3);
+            assert!(chonk.is_empty());
+            assert_eq!(chonk.total_bytes(), 0);
+            assert!(chonk.get(0).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk size must be non-zero")]
+    fn test_write_chunked_zero_size() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABC"), 0);
+        });
+    }
+
     #[test]
     fn test_set() {
         let env = Env::default();
@@ -291,4 +355,3373 @@ mod tests {
             assert_eq!(chonk.meta().version, 4);
         });
     }
+
+    #[test]
+    fn test_try_push_count_overflow() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            let full = ChonkMeta {
+                count: u32::MAX,
+                ..ChonkMeta::new()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &full);
+
+            let data = Bytes::from_slice(&env, b"A");
+            assert_eq!(
+                chonk.try_push(data.clone()),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+            assert_eq!(
+                chonk.try_insert(0, data),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+
+            // Nothing was written
+            assert_eq!(chonk.meta(), full);
+            assert!(chonk.get(u32::MAX).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_insert_many_version_overflow() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let exhausted = ChonkMeta {
+                version: u64::MAX,
+                ..ChonkMeta::new()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &exhausted);
+
+            let chunks = soroban_sdk::Vec::from_array(&env, [Bytes::from_slice(&env, b"A")]);
+            chonk.insert_many(0, chunks);
+        });
+    }
+
+    #[test]
+    fn test_try_mutators_checked() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            assert_eq!(chonk.try_push(Bytes::from_slice(&env, b"AB")), Ok(0));
+            assert_eq!(
+                chonk.try_set(1, Bytes::from_slice(&env, b"C")),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+            assert_eq!(
+                chonk.try_insert(2, Bytes::from_slice(&env, b"C")),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+            assert_eq!(chonk.try_remove(1), Ok(None));
+
+            // Craft a meta whose total_bytes is near the limit
+            let meta = ChonkMeta {
+                total_bytes: u64::MAX - 1,
+                ..chonk.meta()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &meta);
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"CD")),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+
+            // And one whose total_bytes is smaller than the stored chunk
+            let meta = ChonkMeta {
+                total_bytes: 1,
+                ..chonk.meta()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &meta);
+            assert_eq!(chonk.try_remove(0), Err(ChonkError::StorageLimitExceeded));
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"AB")));
+        });
+    }
+
+    #[test]
+    fn test_try_push_limits() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"))
+                .with_max_chunk_size(4)
+                .with_max_total_bytes(6);
+
+            assert_eq!(chonk.try_push(Bytes::from_slice(&env, b"ABCD")), Ok(0));
+
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"EFGHI")),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"EFG")),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+
+            assert_eq!(chonk.try_push(Bytes::from_slice(&env, b"EF")), Ok(1));
+            assert_eq!(chonk.count(), 2);
+            assert_eq!(chonk.total_bytes(), 6);
+            assert_eq!(chonk.meta().version, 2);
+        });
+    }
+
+    #[test]
+    fn test_compare_and_set() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            let old = Bytes::from_slice(&env, b"old");
+            let new = Bytes::from_slice(&env, b"new");
+            chonk.push(old.clone());
+
+            // Expected value doesn't match: nothing written
+            assert_eq!(chonk.compare_and_set(0, &new, new.clone()), Ok(false));
+            assert_eq!(chonk.get(0), Some(old.clone()));
+            assert_eq!(chonk.meta().version, 1);
+
+            // Expected value matches: swap happens
+            assert_eq!(chonk.compare_and_set(0, &old, new.clone()), Ok(true));
+            assert_eq!(chonk.get(0), Some(new.clone()));
+            assert_eq!(chonk.meta().version, 2);
+
+            assert_eq!(
+                chonk.compare_and_set(1, &old, new),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+        });
+    }
+
+    #[test]
+    fn test_set_if_unchanged() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let old = Bytes::from_slice(&env, b"old");
+            let new = Bytes::from_slice(&env, b"new");
+            chonk.push(old.clone());
+
+            assert_eq!(chonk.set_if_unchanged(0, old.clone(), new.clone()), Ok(()));
+            assert_eq!(chonk.get(0), Some(new.clone()));
+
+            // `old` is now stale
+            assert_eq!(
+                chonk.set_if_unchanged(0, old.clone(), Bytes::from_slice(&env, b"lost")),
+                Err(ChonkError::VersionConflict)
+            );
+            assert_eq!(chonk.get(0), Some(new));
+            assert_eq!(chonk.meta().version, 2);
+
+            assert_eq!(
+                chonk.set_if_unchanged(1, old.clone(), old),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+        });
+    }
+
+    #[test]
+    fn test_swap_collections() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let blue = Chonk::open(&env, symbol_short!("blue"));
+            let green = Chonk::open(&env, symbol_short!("green"));
+
+            blue.push(Bytes::from_slice(&env, b"old"));
+            green.push(Bytes::from_slice(&env, b"new "));
+            green.push(Bytes::from_slice(&env, b"content"));
+
+            Chonk::swap_collections(
+                &env,
+                symbol_short!("blue"),
+                symbol_short!("green"),
+                StorageKind::Persistent,
+            );
+
+            assert_eq!(blue.count(), 2);
+            assert_eq!(blue.assemble(), Bytes::from_slice(&env, b"new content"));
+            assert_eq!(blue.total_bytes(), 11);
+
+            assert_eq!(green.count(), 1);
+            assert_eq!(green.assemble(), Bytes::from_slice(&env, b"old"));
+            assert!(green.get(1).is_none());
+
+            // Only collections in the given storage are swapped
+            let kind = StorageKind::Temporary;
+            let temp = Chonk::open_with(&env, symbol_short!("blue"), kind);
+            let idle = Chonk::open_with(&env, symbol_short!("idle"), kind);
+            temp.push(Bytes::from_slice(&env, b"temp"));
+            Chonk::swap_collections(&env, symbol_short!("blue"), symbol_short!("idle"), kind);
+            assert!(temp.is_empty());
+            assert_eq!(idle.assemble(), Bytes::from_slice(&env, b"temp"));
+            assert_eq!(blue.assemble(), Bytes::from_slice(&env, b"new content"));
+        });
+    }
+
+    #[test]
+    fn test_stable_keys() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        let key_a = chunk_key(&env, symbol_short!("test"), 3);
+        let key_b = chunk_key(&env, symbol_short!("test"), 3);
+        assert_eq!(key_a, key_b);
+        assert_eq!(key_a, ChonkKey::Chunk(symbol_short!("test"), 3));
+        assert_ne!(key_a, chunk_key(&env, symbol_short!("test"), 4));
+        assert_eq!(
+            meta_key(&env, symbol_short!("test")),
+            ChonkKey::Meta(symbol_short!("test"))
+        );
+
+        // The keys address the entries a Chonk writes
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"A"));
+
+            let key = chunk_key(&env, symbol_short!("test"), 0);
+            let stored: Option<Bytes> = env.storage().persistent().get(&key);
+            assert_eq!(stored, Some(Bytes::from_slice(&env, b"A")));
+
+            let key = meta_key(&env, symbol_short!("test"));
+            let meta: Option<ChonkMeta> = env.storage().persistent().get(&key);
+            assert_eq!(meta, Some(chonk.meta()));
+        });
+    }
+
+    #[test]
+    fn test_reverse_iter() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            for s in [b"A", b"B", b"C", b"D"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+
+            let chunks: std::vec::Vec<Bytes> = chonk.reverse_iter_from(2).collect();
+            assert_eq!(
+                chunks,
+                std::vec![
+                    Bytes::from_slice(&env, b"C"),
+                    Bytes::from_slice(&env, b"B"),
+                    Bytes::from_slice(&env, b"A"),
+                ]
+            );
+
+            // Index past the end is clamped to the last chunk
+            assert_eq!(chonk.reverse_iter_from(100).len(), 4);
+            assert_eq!(
+                chonk.reverse_iter_from(100).next(),
+                Some(Bytes::from_slice(&env, b"D"))
+            );
+
+            // Both ends of the iterator can be consumed
+            let mut iter = chonk.iter();
+            assert_eq!(iter.next(), Some(Bytes::from_slice(&env, b"A")));
+            assert_eq!(iter.next_back(), Some(Bytes::from_slice(&env, b"D")));
+            assert_eq!(iter.len(), 2);
+
+            let empty = Chonk::open(&env, symbol_short!("empty"));
+            assert_eq!(empty.reverse_iter_from(2).count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_count_fast() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.count_fast(), 0);
+
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            chonk.push(Bytes::from_slice(&env, b"B"));
+            chonk.insert(0, Bytes::from_slice(&env, b"C"));
+            assert_eq!(chonk.count_fast(), 3);
+            assert_eq!(chonk.count_fast(), chonk.count());
+
+            chonk.remove(1);
+            chonk.set(0, Bytes::from_slice(&env, b"D"));
+            assert_eq!(chonk.count_fast(), 2);
+            assert_eq!(chonk.count_fast(), chonk.count());
+
+            chonk.clear();
+            assert_eq!(chonk.count_fast(), 0);
+        });
+    }
+
+    #[test]
+    fn test_map_collect() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let src = Chonk::open(&env, symbol_short!("src"));
+            src.push(Bytes::from_slice(&env, b"A"));
+            src.push(Bytes::from_slice(&env, b"BC"));
+
+            let dest = Chonk::open(&env, symbol_short!("dest"));
+            dest.push(Bytes::from_slice(&env, b"stale"));
+            dest.push(Bytes::from_slice(&env, b"stale"));
+            dest.push(Bytes::from_slice(&env, b"stale"));
+
+            src.map_collect(symbol_short!("dest"), |mut chunk| {
+                chunk.push_back(b'!');
+                chunk
+            });
+
+            assert_eq!(dest.count(), 2);
+            assert_eq!(dest.total_bytes(), 5);
+            assert_eq!(dest.get(0), Some(Bytes::from_slice(&env, b"A!")));
+            assert_eq!(dest.get(1), Some(Bytes::from_slice(&env, b"BC!")));
+            assert!(dest.get(2).is_none());
+
+            // Source is untouched
+            assert_eq!(src.assemble(), Bytes::from_slice(&env, b"ABC"));
+        });
+    }
+
+    #[test]
+    fn test_map_collect_refreshes_cache() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let cache = ChonkCache::new();
+            let src = Chonk::open_cached(&env, symbol_short!("src"), &cache);
+            src.write_chunked(Bytes::from_slice(&env, b"abc"), 1);
+
+            // Onto its own id: the cached metadata follows the rewrite
+            src.map_collect(symbol_short!("src"), |mut chunk| {
+                chunk.push_back(b'!');
+                chunk
+            });
+            assert_eq!(src.total_bytes(), 6);
+
+            // Onto a collection whose metadata the cache holds
+            let dest = Chonk::open_cached(&env, symbol_short!("dest"), &cache);
+            dest.push(Bytes::from_slice(&env, b"stale"));
+            src.map_collect(symbol_short!("dest"), |chunk| chunk);
+            assert_eq!(dest.count(), 3);
+            assert_eq!(dest.assemble(), Bytes::from_slice(&env, b"a!b!c!"));
+        });
+    }
+
+    #[test]
+    fn test_count_occurrences() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            // "cat" once inside a chunk and once straddling a boundary
+            chonk.push(Bytes::from_slice(&env, b"a cat and a c"));
+            chonk.push(Bytes::from_slice(&env, b"at"));
+
+            assert_eq!(chonk.count_occurrences(&Bytes::from_slice(&env, b"cat")), 2);
+            assert_eq!(chonk.count_occurrences(&Bytes::from_slice(&env, b"dog")), 0);
+
+            // Matches don't overlap
+            let runs = Chonk::open(&env, symbol_short!("runs"));
+            runs.push(Bytes::from_slice(&env, b"aa"));
+            runs.push(Bytes::from_slice(&env, b"aaa"));
+            assert_eq!(runs.count_occurrences(&Bytes::from_slice(&env, b"aa")), 2);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern must not be empty")]
+    fn test_count_occurrences_empty_pattern() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.count_occurrences(&Bytes::new(&env));
+        });
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            for s in [b"A", b"B", b"C"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+            let version = chonk.meta().version;
+
+            let mut chunks = soroban_sdk::Vec::new(&env);
+            chunks.push_back(Bytes::from_slice(&env, b"X"));
+            chunks.push_back(Bytes::from_slice(&env, b"Y"));
+            chonk.insert_many(1, chunks);
+
+            assert_eq!(chonk.count(), 5);
+            assert_eq!(chonk.total_bytes(), 5);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AXYBC"));
+            assert_eq!(chonk.meta().version, version + 1);
+        });
+    }
+
+    #[test]
+    fn test_index_of_byte() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            chonk.push(Bytes::from_slice(&env, b"key="));
+            chonk.push(Bytes::from_slice(&env, b"value\n"));
+            chonk.push(Bytes::from_slice(&env, b"more\n"));
+
+            assert_eq!(chonk.index_of_byte(b'\n'), Some(9));
+            assert_eq!(chonk.index_of_byte(b'='), Some(3));
+            assert_eq!(chonk.index_of_byte(b'#'), None);
+        });
+    }
+
+    #[test]
+    fn test_remove_range() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            for s in [b"A", b"B", b"C", b"D", b"E"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+            let version = chonk.meta().version;
+
+            chonk.remove_range(1, 3);
+
+            assert_eq!(chonk.count(), 3);
+            assert_eq!(chonk.total_bytes(), 3);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ADE"));
+            assert_eq!(chonk.meta().version, version + 1);
+            assert!(chonk.get(3).is_none());
+            assert!(chonk.get(4).is_none());
+
+            // End is clamped to count
+            chonk.remove_range(1, 100);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"A"));
+
+            // Empty range is a no-op
+            chonk.remove_range(1, 1);
+            assert_eq!(chonk.meta().version, version + 2);
+        });
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test")).with_max_chunk_size(4);
+
+            chonk.push(Bytes::from_slice(&env, b"ab{"));
+            chonk.push(Bytes::from_slice(&env, b"}cd"));
+            let version = chonk.meta().version;
+
+            // "{}" straddles the chunk boundary and the content grows
+            chonk.replace_all(
+                &Bytes::from_slice(&env, b"{}"),
+                &Bytes::from_slice(&env, b"<none>"),
+            );
+
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ab<none>cd"));
+            assert_eq!(chonk.total_bytes(), 10);
+            assert_eq!(chonk.count(), 3);
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"ab<n")));
+            assert_eq!(chonk.meta().version, version + 1);
+
+            // Shrinking works too and drops the stale tail chunk
+            chonk.replace_all(
+                &Bytes::from_slice(&env, b"<none>"),
+                &Bytes::from_slice(&env, b"-"),
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ab-cd"));
+            assert_eq!(chonk.count(), 2);
+            assert!(chonk.get(2).is_none());
+
+            // No match leaves the collection untouched
+            chonk.replace_all(&Bytes::from_slice(&env, b"zz"), &Bytes::new(&env));
+            assert_eq!(chonk.meta().version, version + 2);
+        });
+    }
+
+    #[test]
+    fn test_replace_all_keeps_chunk_size() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .chunk_size(4)
+                .max_chunk_size(8)
+                .build();
+            chonk.write_chunked(Bytes::from_slice(&env, b"aaaa-bbb"), 4);
+
+            chonk.replace_all(
+                &Bytes::from_slice(&env, b"-"),
+                &Bytes::from_slice(&env, b"---"),
+            );
+            assert_eq!(chonk.lengths().collect::<std::vec::Vec<_>>(), [4, 4, 2]);
+            assert_eq!(chonk.try_append(Bytes::from_slice(&env, b"c"), 4), Ok(()));
+        });
+    }
+
+    #[test]
+    fn test_lengths() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            chonk.push(Bytes::from_slice(&env, b"BCD"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"EF"));
+
+            let lengths: std::vec::Vec<u32> = chonk.lengths().collect();
+            assert_eq!(lengths, std::vec![1, 3, 0, 2]);
+            assert_eq!(chonk.lengths().sum::<u32>() as u64, chonk.total_bytes());
+        });
+    }
+
+    #[test]
+    fn test_clone_handle() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let other = chonk.clone();
+
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            other.push(Bytes::from_slice(&env, b"B"));
+
+            assert_eq!(other.id(), chonk.id());
+            assert_eq!(chonk.count(), 2);
+            assert_eq!(other.assemble(), Bytes::from_slice(&env, b"AB"));
+            assert_eq!(chonk.meta(), other.meta());
+        });
+    }
+
+    #[test]
+    fn test_env_accessor() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(chonk.env(), b"built from the handle"));
+            assert_eq!(
+                chonk.assemble(),
+                Bytes::from_slice(&env, b"built from the handle")
+            );
+        });
+    }
+
+    trait ChonkExt {
+        fn push_reversed(&self, data: Bytes) -> u32;
+    }
+
+    // Built only on the public API, as a downstream crate would
+    impl ChonkExt for Chonk<'_> {
+        fn push_reversed(&self, data: Bytes) -> u32 {
+            let mut reversed = Bytes::new(self.env());
+            for byte in data.iter().rev() {
+                reversed.push_back(byte);
+            }
+            self.push(reversed)
+        }
+    }
+
+    #[test]
+    fn test_extension_trait() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .temporary()
+                .build();
+            chonk.push(Bytes::from_slice(&env, b"ab"));
+            assert_eq!(chonk.push_reversed(Bytes::from_slice(&env, b"xyz")), 1);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abzyx"));
+
+            // The stored chunk can be read back with the public key helpers
+            assert_eq!(chonk.storage_kind(), StorageKind::Temporary);
+            let key = chunk_key(chonk.env(), chonk.id().clone(), 1);
+            let stored: Option<Bytes> = env.storage().temporary().get(&key);
+            assert_eq!(stored, Some(Bytes::from_slice(&env, b"zyx")));
+        });
+    }
+
+    #[test]
+    fn test_chunk_key_resolves_layout() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let gapped = Chonk::open_gapped(&env, symbol_short!("gapped"));
+            gapped.push(Bytes::from_slice(&env, b"A"));
+            gapped.push(Bytes::from_slice(&env, b"C"));
+            gapped.insert(1, Bytes::from_slice(&env, b"B"));
+
+            // "B" went into a fresh slot, out of index order
+            let key = gapped.chunk_key(1).unwrap();
+            assert_eq!(key, ChonkKey::Slot(symbol_short!("gapped"), 2));
+            let stored: Option<Bytes> = env.storage().persistent().get(&key);
+            assert_eq!(stored, Some(Bytes::from_slice(&env, b"B")));
+            assert_eq!(gapped.chunk_key(3), None);
+
+            let linked = Chonk::open_linked(&env, symbol_short!("linked"));
+            linked.push(Bytes::from_slice(&env, b"A"));
+            linked.insert(0, Bytes::from_slice(&env, b"Z"));
+            let key = linked.chunk_key(0).unwrap();
+            let node: ChonkNode = env.storage().persistent().get(&key).unwrap();
+            assert_eq!(node.data, Bytes::from_slice(&env, b"Z"));
+
+            let dense = Chonk::open(&env, symbol_short!("dense"));
+            dense.push(Bytes::from_slice(&env, b"A"));
+            assert_eq!(
+                dense.chunk_key(0),
+                Some(chunk_key(&env, symbol_short!("dense"), 0))
+            );
+        });
+    }
+
+    #[test]
+    fn test_total_bytes_above_u32() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let large = u32::MAX as u64 + 5;
+
+            let meta = ChonkMeta {
+                total_bytes: large,
+                ..ChonkMeta::new()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &meta);
+            assert_eq!(chonk.total_bytes(), large);
+
+            chonk.push(Bytes::from_slice(&env, b"ABC"));
+            assert_eq!(chonk.total_bytes(), large + 3);
+
+            chonk.set(0, Bytes::from_slice(&env, b"A"));
+            assert_eq!(chonk.total_bytes(), large + 1);
+
+            chonk.remove(0);
+            assert_eq!(chonk.total_bytes(), large);
+        });
+    }
+
+    #[test]
+    fn test_legacy_meta_upgrade() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            // Entries as written by a 1.0 release
+            let key = ChonkKey::Meta(symbol_short!("test"));
+            let legacy = types::ChonkMetaV1 {
+                count: 1,
+                total_bytes: 2,
+                version: 7,
+            };
+            env.storage().persistent().set(&key, &legacy);
+            env.storage().persistent().set(
+                &ChonkKey::Chunk(symbol_short!("test"), 0),
+                &Bytes::from_slice(&env, b"AB"),
+            );
+
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(
+                chonk.meta(),
+                ChonkMeta {
+                    count: 1,
+                    total_bytes: 2,
+                    version: 7,
+                    layout: ChonkLayout::Dense,
+                    append_only: false,
+                    events: false,
+                    journal: false,
+                    chunk_size: None,
+                    content_type: None,
+                    owner: None,
+                    hashed: false,
+                    content_hash: None,
+                    hash_version: 0,
+                }
+            );
+
+            // The next write stores the current layout
+            chonk.push(Bytes::from_slice(&env, b"C"));
+            let stored: ChonkMeta = env.storage().persistent().get(&key).unwrap();
+            assert_eq!(stored.total_bytes, 3);
+            assert_eq!(stored.version, 8);
+        });
+    }
+
+    #[test]
+    fn test_meta_checked() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            assert!(!chonk.exists());
+            assert_eq!(chonk.meta_checked(), Err(ChonkError::NotFound));
+
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            assert!(chonk.exists());
+            assert_eq!(chonk.meta_checked(), Ok(chonk.meta()));
+
+            chonk.clear();
+            assert!(!chonk.exists());
+            assert_eq!(chonk.meta_checked(), Err(ChonkError::NotFound));
+        });
+    }
+
+    #[test]
+    fn test_clear_counting() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            for s in [b"A", b"B", b"C"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+
+            assert_eq!(chonk.clear_counting(), 3);
+            assert!(chonk.is_empty());
+            assert_eq!(chonk.clear_counting(), 0);
+        });
+    }
+
+    #[test]
+    fn test_version_continuity() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            // Start just below where a 32-bit version would wrap
+            let meta = ChonkMeta {
+                version: u32::MAX as u64 - 1,
+                ..ChonkMeta::new()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &meta);
+
+            let mut last = chonk.meta().version;
+            for i in 0..5u8 {
+                chonk.push(Bytes::from_slice(&env, &[i]));
+                let version = chonk.meta().version;
+                assert_eq!(version, last + 1);
+                last = version;
+            }
+            assert_eq!(last, u32::MAX as u64 + 4);
+
+            // The checked path still refuses to wrap a u64
+            let meta = ChonkMeta {
+                version: u64::MAX,
+                ..chonk.meta()
+            };
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &meta);
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"X")),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+        });
+    }
+
+    #[test]
+    fn test_write_chunked_single_meta_update() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+
+            for s in [b"old1", b"old2", b"old3", b"old4", b"old5"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+            let version = chonk.meta().version;
+
+            let content = Bytes::from_slice(&env, b"ABCDEFGHIJ");
+            chonk.write_chunked(content.clone(), 3);
+
+            // Same layout as chunking by hand
+            assert_eq!(chonk.count(), 4);
+            assert_eq!(chonk.total_bytes(), 10);
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"ABC")));
+            assert_eq!(chonk.get(3), Some(Bytes::from_slice(&env, b"J")));
+            assert!(chonk.get(4).is_none());
+            assert_eq!(chonk.assemble(), content);
+            assert_eq!(chonk.count_fast(), 4);
+
+            assert_eq!(chonk.meta().version, version + 1);
+        });
+    }
+
+    #[test]
+    fn test_insert_write_count() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for s in [b"A", b"B", b"C", b"D"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+        });
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.insert(1, Bytes::from_slice(&env, b"X"));
+        });
+
+        // count - index shifted chunks plus the new one, plus the metadata
+        // and count entries
+        let (count, index) = (4, 1);
+        let resources = env.cost_estimate().resources();
+        assert_eq!(resources.write_entries, (count - index + 1) + 2);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AXBCD"));
+        });
+    }
+
+    #[test]
+    fn test_open_cached() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let cache = ChonkCache::new();
+            let writer = Chonk::open_cached(&env, symbol_short!("test"), &cache);
+            let reader = Chonk::open_cached(&env, symbol_short!("test"), &cache);
+
+            writer.push(Bytes::from_slice(&env, b"A"));
+            writer.push(Bytes::from_slice(&env, b"B"));
+            assert_eq!(reader.count(), 2);
+            assert_eq!(reader.assemble(), Bytes::from_slice(&env, b"AB"));
+
+            // Overwrite the stored metadata behind the cache's back: cached
+            // handles keep answering from the cache rather than storage
+            env.storage()
+                .persistent()
+                .set(&ChonkKey::Meta(symbol_short!("test")), &ChonkMeta::new());
+            assert_eq!(reader.count(), 2);
+            assert_eq!(Chonk::open(&env, symbol_short!("test")).count(), 0);
+
+            reader.clear();
+            assert!(writer.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_cache_shared_across_ids() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let cache = ChonkCache::new();
+            let a = Chonk::open_cached(&env, symbol_short!("a"), &cache);
+            let b = Chonk::open_cached(&env, symbol_short!("b"), &cache);
+
+            a.push(Bytes::from_slice(&env, b"one"));
+            a.push(Bytes::from_slice(&env, b"two"));
+            // `b` misses on `a`'s cached metadata rather than adopting it
+            b.push(Bytes::from_slice(&env, b"x"));
+
+            assert_eq!(b.count(), 1);
+            assert_eq!(a.count(), 2);
+            assert_eq!(Chonk::open(&env, symbol_short!("b")).total_bytes(), 1);
+            assert_eq!(Chonk::open(&env, symbol_short!("a")).total_bytes(), 6);
+        });
+    }
+
+    #[test]
+    fn test_gapped_insert_leaves_other_chunks() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+        let slot = |i| ChonkKey::Slot(symbol_short!("test"), i);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_gapped(&env, symbol_short!("test"));
+            for s in [b"A", b"B", b"C", b"D"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+        });
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_gapped(&env, symbol_short!("test"));
+            chonk.insert(1, Bytes::from_slice(&env, b"X"));
+        });
+
+        // Only the new slot plus the metadata and count entries
+        assert_eq!(env.cost_estimate().resources().write_entries, 3);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_gapped(&env, symbol_short!("test"));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AXBCD"));
+            assert_eq!(chonk.get(1), Some(Bytes::from_slice(&env, b"X")));
+
+            // Existing chunks stay under their original slots
+            let storage = env.storage().persistent();
+            for (i, s) in [b"A", b"B", b"C", b"D", b"X"].iter().enumerate() {
+                let chunk: Option<Bytes> = storage.get(&slot(i as u32));
+                assert_eq!(chunk, Some(Bytes::from_slice(&env, *s)));
+            }
+            assert!(!storage.has(&ChonkKey::Chunk(symbol_short!("test"), 0)));
+
+            assert_eq!(chonk.remove(2), Some(Bytes::from_slice(&env, b"B")));
+            assert!(!storage.has(&slot(1)));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AXCD"));
+            assert_eq!(chonk.total_bytes(), 4);
+        });
+    }
+
+    #[test]
+    fn test_gapped_layout_operations() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let gapped = Chonk::open_gapped(&env, symbol_short!("gap"));
+            gapped.write_chunked(Bytes::from_slice(&env, b"abcdef"), 2);
+            let mut chunks = soroban_sdk::Vec::new(&env);
+            chunks.push_back(Bytes::from_slice(&env, b"12"));
+            chunks.push_back(Bytes::from_slice(&env, b"34"));
+            gapped.insert_many(1, chunks);
+            gapped.remove_range(0, 1);
+            assert_eq!(gapped.assemble(), Bytes::from_slice(&env, b"1234cdef"));
+            assert!(matches!(gapped.meta().layout, ChonkLayout::Gapped(_)));
+
+            // A plain handle follows the layout recorded in the metadata
+            let plain = Chonk::open(&env, symbol_short!("gap"));
+            assert_eq!(plain.get(0), Some(Bytes::from_slice(&env, b"12")));
+            plain.insert(0, Bytes::from_slice(&env, b"<"));
+            plain.set(4, Bytes::from_slice(&env, b">"));
+            assert_eq!(plain.assemble(), Bytes::from_slice(&env, b"<1234cd>"));
+
+            // Rewriting compacts the slots
+            gapped.write_chunked(Bytes::from_slice(&env, b"xyz"), 2);
+            let ChonkLayout::Gapped(slots) = gapped.meta().layout else {
+                panic!("expected gapped layout");
+            };
+            assert_eq!(slots.next, 2);
+            assert!(
+                !env.storage()
+                    .persistent()
+                    .has(&ChonkKey::Slot(symbol_short!("gap"), 5))
+            );
+
+            let dense = Chonk::open(&env, symbol_short!("dense"));
+            dense.push(Bytes::from_slice(&env, b"dense"));
+            Chonk::swap_collections(
+                &env,
+                symbol_short!("gap"),
+                symbol_short!("dense"),
+                StorageKind::Persistent,
+            );
+            assert_eq!(dense.assemble(), Bytes::from_slice(&env, b"xyz"));
+            assert_eq!(plain.assemble(), Bytes::from_slice(&env, b"dense"));
+            assert_eq!(dense.get(1), Some(Bytes::from_slice(&env, b"z")));
+
+            dense.clear();
+            assert!(dense.is_empty());
+            assert_eq!(dense.get(0), None);
+        });
+    }
+
+    #[test]
+    fn test_builder_temporary_append_only() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let log = Chonk::builder(&env, symbol_short!("log"))
+                .storage(StorageKind::Temporary)
+                .max_chunk_size(4)
+                .append_only(true)
+                .build();
+
+            log.push(Bytes::from_slice(&env, b"ab"));
+            log.append(Bytes::from_slice(&env, b"cd"), 4);
+            log.insert(1, Bytes::from_slice(&env, b"ef"));
+            assert_eq!(
+                log.try_push(Bytes::from_slice(&env, b"toolong")),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(log.assemble(), Bytes::from_slice(&env, b"abcdef"));
+
+            // Existing chunks are frozen
+            let x = Bytes::from_slice(&env, b"x");
+            assert_eq!(log.try_set(0, x.clone()), Err(ChonkError::AppendOnly));
+            assert_eq!(log.try_insert(0, x.clone()), Err(ChonkError::AppendOnly));
+            assert_eq!(log.try_remove(1), Err(ChonkError::AppendOnly));
+
+            // Entries live in temporary storage only
+            let key = ChonkKey::Chunk(symbol_short!("log"), 0);
+            assert!(env.storage().temporary().has(&key));
+            assert!(!env.storage().persistent().has(&key));
+
+            // The flag is persisted in meta for later handles
+            let later = Chonk::builder(&env, symbol_short!("log"))
+                .storage(StorageKind::Temporary)
+                .build();
+            assert!(later.meta().append_only);
+            assert_eq!(later.try_set(1, x), Err(ChonkError::AppendOnly));
+            assert_eq!(later.count(), 2);
+        });
+    }
+
+    #[test]
+    fn test_builder_events() {
+        use soroban_sdk::testutils::Events;
+
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .with_events(true)
+                .build();
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            chonk.push(Bytes::from_slice(&env, b"B"));
+            assert_eq!(env.events().all().len(), 2);
+
+            // Later handles keep publishing
+            let plain = Chonk::open(&env, symbol_short!("test"));
+            plain.remove(0);
+            assert_eq!(env.events().all().len(), 3);
+        });
+    }
+
+    #[test]
+    fn test_linked_insert_and_remove() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_linked(&env, symbol_short!("test"));
+            for s in [b"A", b"B", b"C", b"D", b"E"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+        });
+
+        // Insert after "B"
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_linked(&env, symbol_short!("test"));
+            chonk.insert(2, Bytes::from_slice(&env, b"X"));
+        });
+
+        // The new node, its two neighbours, and the metadata and count entries
+        assert_eq!(env.cost_estimate().resources().write_entries, 5);
+
+        // Remove "C"
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_linked(&env, symbol_short!("test"));
+            assert_eq!(chonk.remove(3), Some(Bytes::from_slice(&env, b"C")));
+        });
+
+        // The removed node, its two neighbours, and the metadata and count entries
+        assert_eq!(env.cost_estimate().resources().write_entries, 5);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_linked(&env, symbol_short!("test"));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ABXDE"));
+            assert_eq!(chonk.get(2), Some(Bytes::from_slice(&env, b"X")));
+            assert_eq!(chonk.total_bytes(), 5);
+
+            let node = |i| -> ChonkNode {
+                env.storage()
+                    .persistent()
+                    .get(&ChonkKey::Node(symbol_short!("test"), i))
+                    .unwrap()
+            };
+            // Unrelated nodes keep their ids and links
+            assert_eq!(node(0).next, Some(1));
+            assert_eq!(node(4).prev, Some(3));
+            assert_eq!(node(1).next, Some(5));
+            assert_eq!(node(3).prev, Some(5));
+            assert!(
+                !env.storage()
+                    .persistent()
+                    .has(&ChonkKey::Node(symbol_short!("test"), 2))
+            );
+
+            let mut backwards = Bytes::new(&env);
+            for chunk in chonk.reverse_iter_from(3) {
+                backwards.append(&chunk);
+            }
+            assert_eq!(backwards, Bytes::from_slice(&env, b"DXBA"));
+        });
+    }
+
+    #[test]
+    fn test_linked_layout_operations() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_linked(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdef"), 2);
+            chonk.insert(0, Bytes::from_slice(&env, b"<"));
+            chonk.push(Bytes::from_slice(&env, b">"));
+            chonk.set(2, Bytes::from_slice(&env, b"CD"));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"<abCDef>"));
+
+            chonk.remove_range(1, 4);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"<>"));
+            assert_eq!(chonk.remove(0), Some(Bytes::from_slice(&env, b"<")));
+            assert_eq!(chonk.remove(0), Some(Bytes::from_slice(&env, b">")));
+            assert!(chonk.is_empty());
+            let ChonkLayout::Linked(links) = chonk.meta().layout else {
+                panic!("expected linked layout");
+            };
+            assert_eq!((links.head, links.tail), (None, None));
+
+            chonk.replace_all(&Bytes::from_slice(&env, b"x"), &Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"hello"));
+            let plain = Chonk::open(&env, symbol_short!("plain"));
+            plain.push(Bytes::from_slice(&env, b"bye"));
+            Chonk::swap_collections(
+                &env,
+                symbol_short!("test"),
+                symbol_short!("plain"),
+                StorageKind::Persistent,
+            );
+            assert_eq!(plain.get(0), Some(Bytes::from_slice(&env, b"hello")));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"bye"));
+
+            plain.clear();
+            assert!(plain.is_empty());
+            assert!(
+                !env.storage()
+                    .persistent()
+                    .has(&ChonkKey::Node(symbol_short!("plain"), 0))
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_range_indices_and_stepped() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for s in [b"0", b"1", b"2", b"3", b"4", b"5"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+            let joined = |chunks: soroban_sdk::Vec<Bytes>| {
+                let mut out = Bytes::new(&env);
+                for chunk in chunks.iter() {
+                    out.append(&chunk);
+                }
+                out
+            };
+
+            assert_eq!(
+                joined(chonk.get_range_indices(1, 4)),
+                Bytes::from_slice(&env, b"123")
+            );
+            assert_eq!(
+                joined(chonk.get_range_indices(4, 100)),
+                Bytes::from_slice(&env, b"45")
+            );
+            assert!(chonk.get_range_indices(4, 2).is_empty());
+
+            assert_eq!(
+                joined(chonk.get_stepped(0, 6, 2)),
+                Bytes::from_slice(&env, b"024")
+            );
+            assert_eq!(
+                joined(chonk.get_stepped(1, u32::MAX, 2)),
+                Bytes::from_slice(&env, b"135")
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Step must be non-zero")]
+    fn test_get_stepped_zero_step() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.get_stepped(0, 1, 0);
+        });
+    }
+
+    #[test]
+    fn test_last_chunk_remaining() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.last_chunk_remaining(20), 0);
+
+            chonk.push(Bytes::from_slice(&env, b"first chunk"));
+            chonk.push(Bytes::from_slice(&env, b"8 bytes!"));
+            assert_eq!(chonk.last_chunk_remaining(20), 12);
+            assert_eq!(chonk.last_chunk_remaining(8), 0);
+            assert_eq!(chonk.last_chunk_remaining(4), 0);
+
+            // The preview matches what append does
+            chonk.append(Bytes::from_slice(&env, b"twelve bytes"), 20);
+            assert_eq!(chonk.count(), 2);
+            assert_eq!(chonk.last_chunk_remaining(20), 0);
+        });
+    }
+
+    #[test]
+    fn test_replace_if_version() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"original"), 4);
+            let seen = chonk.meta().version;
+
+            // Another writer gets in first
+            chonk.push(Bytes::from_slice(&env, b"!"));
+
+            assert_eq!(
+                chonk.replace_if_version(Bytes::from_slice(&env, b"stale"), 4, seen),
+                Err(ChonkError::VersionConflict)
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"original!"));
+
+            let current = chonk.meta().version;
+            assert_eq!(
+                chonk.replace_if_version(Bytes::from_slice(&env, b"fresh"), 0, current),
+                Err(ChonkError::InvalidChunkSize)
+            );
+            assert_eq!(
+                chonk.replace_if_version(Bytes::from_slice(&env, b"fresh"), 4, current),
+                Ok(())
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"fresh"));
+            assert_eq!(chonk.meta().version, current + 1);
+        });
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"A"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"B"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::new(&env));
+            let version = chonk.meta().version;
+
+            chonk.shrink_to_fit();
+            assert_eq!(chonk.count(), 3);
+            assert_eq!(chonk.meta().version, version + 1);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AB"));
+            // Empty chunks before the last non-empty one stay
+            assert_eq!(chonk.get(1), Some(Bytes::new(&env)));
+            assert!(
+                !env.storage()
+                    .persistent()
+                    .has(&ChonkKey::Chunk(symbol_short!("test"), 3))
+            );
+
+            // Nothing left to trim
+            chonk.shrink_to_fit();
+            assert_eq!(chonk.meta().version, version + 1);
+        });
+    }
+
+    #[test]
+    fn test_dedup_consecutive() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for chunk in [b"A", b"A", b"B", b"B", b"B", b"A"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+            let version = chonk.meta().version;
+
+            chonk.dedup_consecutive();
+            assert_eq!(chonk.count(), 3);
+            assert_eq!(chonk.total_bytes(), 3);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ABA"));
+            assert_eq!(chonk.meta().version, version + 1);
+
+            // No equal neighbours left: nothing is written
+            chonk.dedup_consecutive();
+            assert_eq!(chonk.meta().version, version + 1);
+        });
+    }
+
+    #[test]
+    fn test_dedup_consecutive_rewrites_tail() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .journaled(true)
+                .build();
+            for chunk in [b"A", b"B", b"C", b"C", b"D", b"D"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+            let version = chonk.meta().version;
+
+            // The write starts at the first duplicate, not at 0
+            chonk.dedup_consecutive();
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ABCD"));
+            assert_eq!(chonk.total_bytes(), 4);
+            assert_eq!(
+                chonk.changes_since(version),
+                soroban_sdk::Vec::from_array(&env, [(version + 1, 3)])
+            );
+
+            let gapped = Chonk::open_gapped(&env, symbol_short!("gapped"));
+            for chunk in [b"A", b"A", b"B", b"A"] {
+                gapped.push(Bytes::from_slice(&env, chunk));
+            }
+            gapped.dedup_consecutive();
+            assert_eq!(gapped.assemble(), Bytes::from_slice(&env, b"ABA"));
+            assert_eq!(gapped.count(), 3);
+        });
+    }
+
+    #[test]
+    fn test_write_chunked_returns_count() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let written = chonk.write_chunked(Bytes::from_slice(&env, b"0123456789"), 3);
+            assert_eq!(written, 4);
+            assert_eq!(written, chonk.count());
+            assert_eq!(chonk.get(written - 1), Some(Bytes::from_slice(&env, b"9")));
+
+            assert_eq!(chonk.write_chunked(Bytes::new(&env), 3), 0);
+            assert_eq!(chonk.count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_fill() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"old"));
+            chonk.push(Bytes::from_slice(&env, b"keep"));
+            let version = chonk.meta().version;
+
+            chonk.fill(0, 0, 5);
+            assert_eq!(chonk.get(0), Some(Bytes::from_array(&env, &[0; 5])));
+            assert_eq!(chonk.total_bytes(), 9);
+            assert_eq!(chonk.meta().version, version + 1);
+
+            chonk.fill(1, b'x', 100);
+            assert_eq!(chonk.get(1).unwrap().len(), 100);
+            assert!(chonk.get(1).unwrap().iter().all(|b| b == b'x'));
+            assert_eq!(chonk.total_bytes(), 105);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_fill_out_of_bounds() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.fill(0, 0, 5);
+        });
+    }
+
+    #[test]
+    fn test_for_each_chunk() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for s in [&b"one"[..], b"three", b"ab"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+
+            let mut total = 0u64;
+            let mut last_index = None;
+            chonk.for_each_chunk(|index, chunk| {
+                total += chunk.len() as u64;
+                last_index = Some(index);
+            });
+            assert_eq!(total, chonk.total_bytes());
+            assert_eq!(last_index, Some(2));
+        });
+    }
+
+    #[test]
+    fn test_to_vec() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert!(chonk.to_vec().is_empty());
+
+            for s in [b"A", b"B", b"C"] {
+                chonk.push(Bytes::from_slice(&env, s));
+            }
+            let chunks = chonk.to_vec();
+            assert_eq!(chunks.len(), chonk.count());
+            for (i, chunk) in chunks.iter().enumerate() {
+                assert_eq!(Some(chunk), chonk.get(i as u32));
+            }
+        });
+    }
+
+    #[test]
+    fn test_estimate_capacity() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"))
+                .with_max_chunk_size(100)
+                .with_max_total_bytes(1000);
+            assert_eq!(chonk.estimate_capacity(100), 10);
+            assert_eq!(chonk.estimate_capacity(101), 0);
+
+            let mut last = chonk.estimate_capacity(64);
+            for _ in 0..3 {
+                chonk.push(Bytes::from_array(&env, &[0; 64]));
+                let estimate = chonk.estimate_capacity(64);
+                assert!(estimate < last);
+                last = estimate;
+            }
+            assert_eq!(last, (1000 - 3 * 64) / 64);
+
+            // The estimate is exact with respect to the configured cap
+            for _ in 0..last {
+                chonk.push(Bytes::from_array(&env, &[0; 64]));
+            }
+            assert_eq!(chonk.estimate_capacity(64), 0);
+            assert!(chonk.try_push(Bytes::from_array(&env, &[0; 64])).is_err());
+
+            // Without a configured cap only the chunk count limits capacity
+            let open = Chonk::open(&env, symbol_short!("open"));
+            open.push(Bytes::new(&env));
+            assert_eq!(open.estimate_capacity(4096), u32::MAX - 1);
+        });
+    }
+
+    #[test]
+    fn test_blob_round_trip() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let blob = ChonkBlob::open(&env, symbol_short!("blob"));
+            assert_eq!(blob.get(), Bytes::new(&env));
+
+            let content = Bytes::from_slice(&env, b"a value too large for one entry");
+            blob.set(content.clone(), 8);
+            assert_eq!(blob.get(), content);
+            assert_eq!(Chonk::open(&env, symbol_short!("blob")).count(), 4);
+
+            // Setting again replaces the value
+            blob.set(Bytes::from_slice(&env, b"short"), 8);
+            assert_eq!(blob.get(), Bytes::from_slice(&env, b"short"));
+
+            // A configured handle keeps its storage kind
+            let temp = ChonkBlob::from(
+                Chonk::builder(&env, symbol_short!("blob"))
+                    .storage(StorageKind::Temporary)
+                    .build(),
+            );
+            temp.set(content.clone(), 8);
+            assert_eq!(temp.get(), content);
+            assert_eq!(blob.get(), Bytes::from_slice(&env, b"short"));
+        });
+    }
+
+    #[test]
+    fn test_move_chunk() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let handles = [
+                Chonk::open(&env, symbol_short!("dense")),
+                Chonk::open_gapped(&env, symbol_short!("gapped")),
+                Chonk::open_linked(&env, symbol_short!("linked")),
+            ];
+            for chonk in handles {
+                chonk.write_chunked(Bytes::from_slice(&env, b"ABCD"), 1);
+                let version = chonk.meta().version;
+
+                chonk.move_chunk(0, 2);
+                assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"BCAD"));
+                assert_eq!(chonk.meta().version, version + 1);
+
+                chonk.move_chunk(3, 0);
+                assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"DBCA"));
+                let back: std::vec::Vec<Bytes> = chonk.iter().rev().collect();
+                assert_eq!(back[0], Bytes::from_slice(&env, b"A"));
+                assert_eq!(back[3], Bytes::from_slice(&env, b"D"));
+
+                // Moving a chunk onto itself changes nothing
+                chonk.move_chunk(1, 1);
+                assert_eq!(chonk.meta().version, version + 2);
+                assert_eq!(chonk.count(), 4);
+                assert_eq!(chonk.total_bytes(), 4);
+            }
+        });
+    }
+
+    #[test]
+    fn test_pages() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.pages(3).count(), 0);
+
+            // Page boundaries don't follow chunk boundaries
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABCDEFG"), 2);
+            let pages: std::vec::Vec<Bytes> = chonk.pages(3).collect();
+            assert_eq!(
+                pages,
+                [
+                    Bytes::from_slice(&env, b"ABC"),
+                    Bytes::from_slice(&env, b"DEF"),
+                    Bytes::from_slice(&env, b"G"),
+                ]
+            );
+            assert_eq!(chonk.pages(3).nth(1), Some(Bytes::from_slice(&env, b"DEF")));
+            assert_eq!(chonk.pages(3).nth(3), None);
+            assert_eq!(chonk.pages(7).count(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Page size must be non-zero")]
+    fn test_pages_zero_size() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let _ = Chonk::open(&env, symbol_short!("test")).pages(0);
+        });
+    }
+
+    #[test]
+    fn test_assemble_into() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"body text"), 4);
+
+            let mut buffer = Bytes::from_slice(&env, b"header:");
+            chonk.assemble_into(&mut buffer);
+            assert_eq!(buffer, Bytes::from_slice(&env, b"header:body text"));
+
+            // An empty collection leaves the buffer as it was
+            Chonk::open(&env, symbol_short!("empty")).assemble_into(&mut buffer);
+            assert_eq!(buffer, Bytes::from_slice(&env, b"header:body text"));
+        });
+    }
+
+    #[test]
+    fn test_clear_partial() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABCDE"), 1);
+
+            assert!(!chonk.clear_partial(2));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"ABC"));
+            assert_eq!(chonk.total_bytes(), 3);
+
+            assert!(!chonk.clear_partial(2));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"A"));
+
+            assert!(chonk.clear_partial(2));
+            assert!(!chonk.exists());
+            assert!(chonk.clear_partial(2));
+
+            // Zero removals makes no progress
+            chonk.write_chunked(Bytes::from_slice(&env, b"AB"), 1);
+            assert!(!chonk.clear_partial(0));
+            assert_eq!(chonk.count(), 2);
+        });
+    }
+
+    #[test]
+    fn test_changes_since() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_journaled(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABC"), 1);
+            assert_eq!(chonk.meta().version, 1);
+
+            chonk.set(1, Bytes::from_slice(&env, b"b"));
+            chonk.push(Bytes::from_slice(&env, b"D"));
+            chonk.remove(0);
+
+            assert_eq!(
+                chonk.changes_since(1),
+                soroban_sdk::Vec::from_array(&env, [(2u64, 1u32), (3, 3), (4, 0)])
+            );
+            assert_eq!(chonk.changes_since(4).len(), 0);
+            assert_eq!(chonk.changes_since(0).get(0), Some((1, 0)));
+
+            // Journaling sticks for handles opened without it
+            Chonk::open(&env, symbol_short!("test")).push(Bytes::from_slice(&env, b"E"));
+            assert_eq!(
+                chonk.changes_since(4),
+                soroban_sdk::Vec::from_array(&env, [(5u64, 3u32)])
+            );
+
+            // Clearing drops the journal as the version restarts
+            chonk.clear();
+            assert_eq!(chonk.changes_since(0).len(), 0);
+            assert!(
+                !env.storage()
+                    .persistent()
+                    .has(&ChonkKey::Change(symbol_short!("test"), 1))
+            );
+
+            // Collections that aren't journaled record nothing
+            let plain = Chonk::open(&env, symbol_short!("plain"));
+            plain.push(Bytes::from_slice(&env, b"A"));
+            assert_eq!(plain.changes_since(0).len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_journal_window() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+        let change = |v| ChonkKey::Change(symbol_short!("test"), v);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_journaled(&env, symbol_short!("test"));
+            for _ in 0..JOURNAL_WINDOW + 3 {
+                chonk.push(Bytes::from_slice(&env, b"x"));
+            }
+
+            // Only the last JOURNAL_WINDOW versions are kept
+            let changes = chonk.changes_since(0);
+            assert_eq!(changes.len() as u64, JOURNAL_WINDOW);
+            assert_eq!(changes.get(0), Some((4, 3)));
+            assert!(!env.storage().persistent().has(&change(3)));
+            assert!(env.storage().persistent().has(&change(4)));
+
+            chonk.clear();
+            for v in 1..=JOURNAL_WINDOW + 3 {
+                assert!(!env.storage().persistent().has(&change(v)));
+            }
+        });
+    }
+
+    #[test]
+    fn test_extend_ttl_partial() {
+        use soroban_sdk::testutils::storage::Persistent;
+
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABCDE"), 1);
+
+            let ttl = |key: &ChonkKey| env.storage().persistent().get_ttl(key);
+            let chunk = |i| ChonkKey::Chunk(symbol_short!("test"), i);
+            let meta = ChonkKey::Meta(symbol_short!("test"));
+            let before = ttl(&chunk(0));
+
+            assert_eq!(chonk.extend_ttl_partial(0, 3, 100_000, 100_000), 3);
+            assert_eq!(ttl(&meta), 100_000);
+            assert_eq!(ttl(&chunk(2)), 100_000);
+            assert_eq!(ttl(&chunk(3)), before);
+
+            assert_eq!(chonk.extend_ttl_partial(3, 3, 100_000, 100_000), 5);
+            assert_eq!(ttl(&chunk(4)), 100_000);
+
+            // Past the end there is nothing left to extend
+            assert_eq!(chonk.extend_ttl_partial(5, 3, 100_000, 100_000), 5);
+        });
+    }
+
+    #[test]
+    fn test_compact_into() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let source = Chonk::open(&env, symbol_short!("source"));
+            for piece in [&b"A"[..], b"BCDEF", b"", b"GH", b"IJKLMNO"] {
+                source.push(Bytes::from_slice(&env, piece));
+            }
+
+            assert_eq!(source.compact_into(symbol_short!("dest"), 4), 4);
+            let dest = Chonk::open(&env, symbol_short!("dest"));
+            assert_eq!(dest.assemble(), source.assemble());
+            let lengths: std::vec::Vec<u32> = dest.lengths().collect();
+            assert_eq!(lengths, [4, 4, 4, 3]);
+            assert_eq!(dest.total_bytes(), 15);
+
+            // The source keeps its original chunking
+            assert_eq!(source.count(), 5);
+            assert_eq!(source.get(1), Some(Bytes::from_slice(&env, b"BCDEF")));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Destination must be empty")]
+    fn test_compact_into_non_empty_dest() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let source = Chonk::open(&env, symbol_short!("source"));
+            source.push(Bytes::from_slice(&env, b"A"));
+            Chonk::open(&env, symbol_short!("dest")).push(Bytes::from_slice(&env, b"B"));
+            source.compact_into(symbol_short!("dest"), 4);
+        });
+    }
+
+    #[test]
+    fn test_compact_into_from_policy_handle() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let source = Chonk::builder(&env, symbol_short!("source"))
+                .chunk_size(4)
+                .append_only(true)
+                .build();
+            source.append(Bytes::from_slice(&env, b"ABCDEFGHIJ"), 4);
+
+            assert_eq!(source.compact_into(symbol_short!("dest"), 8), 2);
+
+            // The destination keeps none of the source's policy
+            let dest = Chonk::open(&env, symbol_short!("dest"));
+            let meta = dest.meta();
+            assert_eq!(meta.chunk_size, None);
+            assert!(!meta.append_only);
+            dest.append(Bytes::from_slice(&env, b"KLMNOP"), 8);
+            assert_eq!(dest.lengths().collect::<std::vec::Vec<_>>(), [8, 8]);
+            dest.set(0, Bytes::from_slice(&env, b"abcdefgh"));
+        });
+    }
+
+    #[test]
+    fn test_append_chunk_size_policy() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .chunk_size(4)
+                .build();
+            chonk.append(Bytes::from_slice(&env, b"ABC"), 4);
+            assert_eq!(chonk.meta().chunk_size, Some(4));
+
+            // The stored size applies to handles opened without it
+            let plain = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(
+                plain.try_append(Bytes::from_slice(&env, b"DE"), 8),
+                Err(ChonkError::InvalidChunkSize)
+            );
+            assert_eq!(plain.assemble(), Bytes::from_slice(&env, b"ABC"));
+
+            assert_eq!(plain.try_append(Bytes::from_slice(&env, b"DE"), 4), Ok(()));
+            assert_eq!(plain.count(), 2);
+
+            // Without a stored size any max is accepted
+            let free = Chonk::open(&env, symbol_short!("free"));
+            free.append(Bytes::from_slice(&env, b"AB"), 2);
+            assert_eq!(free.try_append(Bytes::from_slice(&env, b"CD"), 8), Ok(()));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #8)")]
+    fn test_append_chunk_size_conflict_panics() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .chunk_size(4)
+                .build();
+            chonk.append(Bytes::from_slice(&env, b"ABC"), 16);
+        });
+    }
+
+    #[test]
+    fn test_equal_bytes() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"Hello, World!"), 5);
+
+            assert!(chonk.equal_bytes(&Bytes::from_slice(&env, b"Hello, World!")));
+            assert!(!chonk.equal_bytes(&Bytes::from_slice(&env, b"Hello, World?")));
+            assert!(!chonk.equal_bytes(&Bytes::from_slice(&env, b"Hello, World")));
+            assert!(!chonk.equal_bytes(&Bytes::new(&env)));
+
+            let empty = Chonk::open(&env, symbol_short!("empty"));
+            assert!(empty.equal_bytes(&Bytes::new(&env)));
+        });
+    }
+
+    #[test]
+    fn test_is_sorted_by() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let sorted = Chonk::open(&env, symbol_short!("sorted"));
+            sorted.write_chunked(Bytes::from_slice(&env, b"ABC"), 1);
+            assert!(sorted.is_sorted_by(|a, b| a.cmp(b)));
+            assert!(!sorted.is_sorted_by(|a, b| b.cmp(a)));
+
+            let unsorted = Chonk::open(&env, symbol_short!("unsorted"));
+            unsorted.write_chunked(Bytes::from_slice(&env, b"ACB"), 1);
+            assert!(!unsorted.is_sorted_by(|a, b| a.cmp(b)));
+
+            // Equal neighbours don't break the order
+            let by_len = Chonk::open(&env, symbol_short!("by_len"));
+            by_len.write_chunked(Bytes::from_slice(&env, b"ZZYYX"), 2);
+            assert!(by_len.is_sorted_by(|a, b| b.len().cmp(&a.len())));
+
+            assert!(Chonk::open(&env, symbol_short!("empty")).is_sorted_by(|a, b| a.cmp(b)));
+        });
+    }
+
+    #[test]
+    fn test_push_slice() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.push_slice(b"hello"), 0);
+            assert_eq!(chonk.push_slice(b""), 1);
+
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"hello")));
+            assert_eq!(chonk.get(1), Some(Bytes::new(&env)));
+            assert_eq!(chonk.total_bytes(), 5);
+        });
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let handles = [
+                Chonk::open(&env, symbol_short!("dense")),
+                Chonk::open_gapped(&env, symbol_short!("gapped")),
+                Chonk::open_linked(&env, symbol_short!("linked")),
+            ];
+            for chonk in handles {
+                chonk.write_chunked(Bytes::from_slice(&env, b"cab"), 1);
+                let version = chonk.meta().version;
+
+                chonk.sort_by(|a, b| a.cmp(b));
+                assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abc"));
+                assert_eq!(chonk.get(2), Some(Bytes::from_slice(&env, b"c")));
+                assert_eq!(chonk.meta().version, version + 1);
+
+                // Already sorted: nothing is written
+                chonk.sort_by(|a, b| a.cmp(b));
+                assert_eq!(chonk.meta().version, version + 1);
+            }
+
+            // Equal chunks keep their relative order
+            let stable = Chonk::open(&env, symbol_short!("stable"));
+            stable.write_chunked(Bytes::from_slice(&env, b"bbayax"), 2);
+            stable.sort_by(|a, b| a.get(0).cmp(&b.get(0)));
+            assert_eq!(stable.assemble(), Bytes::from_slice(&env, b"ayaxbb"));
+        });
+    }
+
+    #[test]
+    fn test_sort_by_len() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for chunk in [&b"ccc"[..], b"a", b"dddd", b"b", b"ee"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+            let (total, version) = (chonk.total_bytes(), chonk.meta().version);
+            assert!(!chonk.is_sorted_by_len());
+
+            chonk.sort_by_len();
+            assert!(chonk.is_sorted_by_len());
+            // Equal lengths keep their relative order
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abeecccdddd"));
+            assert_eq!(chonk.total_bytes(), total);
+            assert_eq!(chonk.meta().version, version + 1);
+        });
+    }
+
+    #[test]
+    fn test_write_slice_chunked() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.write_slice_chunked(b"0123456789", 4), Ok(3));
+
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"0123")));
+            assert_eq!(chonk.get(1), Some(Bytes::from_slice(&env, b"4567")));
+            assert_eq!(chonk.get(2), Some(Bytes::from_slice(&env, b"89")));
+            assert_eq!(chonk.total_bytes(), 10);
+            assert_eq!(chonk.meta().version, 1);
+
+            assert_eq!(
+                chonk.write_slice_chunked(b"0123", 0),
+                Err(ChonkError::InvalidChunkSize)
+            );
+            assert_eq!(chonk.write_slice_chunked(b"", 4), Ok(0));
+            assert!(chonk.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_replace() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"AABB"), 2);
+
+            let old = chonk.replace(1, Bytes::from_slice(&env, b"CCC"));
+            assert_eq!(old, Some(Bytes::from_slice(&env, b"BB")));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AACCC"));
+            assert_eq!(chonk.total_bytes(), 5);
+            assert_eq!(chonk.meta().version, 2);
+
+            // Out of bounds writes nothing
+            assert_eq!(chonk.replace(2, Bytes::from_slice(&env, b"D")), None);
+            assert_eq!(chonk.meta().version, 2);
+        });
+    }
+
+    #[test]
+    fn test_bounds_helpers() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert!(!chonk.contains_index(0));
+            assert_eq!(chonk.valid_range(), None);
+
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABC"), 1);
+            assert!(chonk.contains_index(0));
+            assert!(chonk.contains_index(2));
+            assert!(!chonk.contains_index(3));
+            assert!(!chonk.contains_index(u32::MAX));
+            assert_eq!(chonk.valid_range(), Some((0, 2)));
+        });
+    }
+
+    #[test]
+    fn test_insert_reporting() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(
+                chonk.insert_reporting(0, Bytes::from_slice(&env, b"AAA")),
+                3
+            );
+
+            let total = chonk.insert_reporting(0, Bytes::from_slice(&env, b"BB"));
+            assert_eq!(total, 5);
+            assert_eq!(total, chonk.meta().total_bytes);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"BBAAA"));
+        });
+    }
+
+    #[test]
+    fn test_trim() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for piece in [&b"   "[..], b"", b"  hi ", b"there  ", b"  "] {
+                chonk.push(Bytes::from_slice(&env, piece));
+            }
+            let version = chonk.meta().version;
+
+            // The leading run covers the first two chunks entirely
+            chonk.trim_start(b' ');
+            assert_eq!(chonk.count(), 3);
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"hi ")));
+            assert_eq!(chonk.meta().version, version + 1);
+
+            chonk.trim_end(b' ');
+            assert_eq!(chonk.count(), 2);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"hi there"));
+            assert_eq!(chonk.total_bytes(), 8);
+            assert_eq!(chonk.meta().version, version + 2);
+
+            // Nothing left to trim
+            chonk.trim_start(b' ');
+            chonk.trim_end(b' ');
+            assert_eq!(chonk.meta().version, version + 2);
+
+            // A collection made only of the byte is emptied
+            chonk.write_chunked(Bytes::from_slice(&env, b"xxxx"), 3);
+            chonk.trim_end(b'x');
+            assert!(chonk.is_empty());
+            assert_eq!(chonk.total_bytes(), 0);
+        });
+    }
+
+    #[test]
+    fn test_try_for_each() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"ABCD"), 1);
+
+            let mut visited = std::vec::Vec::new();
+            let result = chonk.try_for_each(|index, chunk| {
+                visited.push(index);
+                if chunk == Bytes::from_slice(&env, b"B") {
+                    return Err(ChonkError::InvalidFormat);
+                }
+                Ok(())
+            });
+            assert_eq!(result, Err(ChonkError::InvalidFormat));
+            assert_eq!(visited, [0, 1]);
+
+            let mut total = 0;
+            let result: Result<(), ChonkError> = chonk.try_for_each(|_, chunk| {
+                total += chunk.len();
+                Ok(())
+            });
+            assert_eq!(result, Ok(()));
+            assert_eq!(total, 4);
+        });
+    }
+
+    #[test]
+    fn test_chunk_sizes_histogram() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.chunk_sizes_histogram(), (0, 0, 0));
+
+            for piece in [&b"A"[..], b"BCDEFGHIJK", b"LMN", b"OPQR"] {
+                chonk.push(Bytes::from_slice(&env, piece));
+            }
+            assert_eq!(chonk.chunk_sizes_histogram(), (1, 10, 4));
+        });
+    }
+
+    #[test]
+    fn test_view() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_gapped(&env, symbol_short!("test"));
+            let view = chonk.view();
+            assert!(view.is_empty());
+
+            // The view reads through to later writes
+            chonk.write_chunked(Bytes::from_slice(&env, b"Hello"), 2);
+            assert_eq!(view.id(), &symbol_short!("test"));
+            assert_eq!(view.count(), 3);
+            assert_eq!(view.total_bytes(), 5);
+            assert_eq!(view.meta(), chonk.meta());
+            assert_eq!(view.get(1), Some(Bytes::from_slice(&env, b"ll")));
+            assert_eq!(view.get_range(1, 2).len(), 2);
+            assert_eq!(view.iter().count(), 3);
+            assert_eq!(view.assemble(), Bytes::from_slice(&env, b"Hello"));
+        });
+    }
+
+    #[test]
+    fn test_assemble_reversed() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let log = Chonk::open(&env, symbol_short!("log"));
+            let reversed = Chonk::open(&env, symbol_short!("reversed"));
+            for entry in [&b"first;"[..], b"second;", b"third;"] {
+                log.push(Bytes::from_slice(&env, entry));
+                reversed.insert(0, Bytes::from_slice(&env, entry));
+            }
+
+            assert_eq!(log.assemble_reversed(), reversed.assemble());
+            assert_eq!(
+                log.assemble_reversed(),
+                Bytes::from_slice(&env, b"third;second;first;")
+            );
+            assert_eq!(
+                Chonk::open(&env, symbol_short!("empty")).assemble_reversed(),
+                Bytes::new(&env)
+            );
+        });
+    }
+
+    #[test]
+    fn test_content_type() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("logo"));
+            assert_eq!(chonk.content_type(), None);
+
+            chonk.write_chunked(Bytes::from_slice(&env, b"<svg/>"), 4);
+            chonk.set_content_type(Bytes::from_slice(&env, b"image/svg+xml"));
+            assert_eq!(
+                chonk.content_type(),
+                Some(Bytes::from_slice(&env, b"image/svg+xml"))
+            );
+            assert_eq!(chonk.meta().version, 1);
+
+            // The tag survives rewrites of the content
+            chonk.write_chunked(Bytes::from_slice(&env, b"<svg></svg>"), 4);
+            assert_eq!(
+                Chonk::open(&env, symbol_short!("logo")).content_type(),
+                Some(Bytes::from_slice(&env, b"image/svg+xml"))
+            );
+
+            chonk.clear();
+            assert_eq!(chonk.content_type(), None);
+        });
+    }
+
+    #[test]
+    fn test_builder_options() {
+        use soroban_sdk::testutils::{Address as _, Events, storage::Temporary};
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = test_contract_id(&env);
+        let owner = soroban_sdk::Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .temporary()
+                .with_events(true)
+                .max_chunk_size(8)
+                .max_total_bytes(12)
+                .ttl(1_000, 2_000)
+                .owner(owner.clone())
+                .open();
+
+            assert_eq!(chonk.try_push(Bytes::from_slice(&env, b"ABCDEFGH")), Ok(0));
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"ABCDEFGHI")),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(
+                chonk.try_push(Bytes::from_slice(&env, b"ABCDE")),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+            assert_eq!(env.events().all().len(), 1);
+
+            let temporary = env.storage().temporary();
+            let chunk_key = ChonkKey::Chunk(symbol_short!("test"), 0);
+            assert!(temporary.has(&chunk_key));
+            assert!(!env.storage().persistent().has(&chunk_key));
+            assert_eq!(temporary.get_ttl(&chunk_key), 2_000);
+            assert_eq!(
+                temporary.get_ttl(&ChonkKey::Meta(symbol_short!("test"))),
+                2_000
+            );
+
+            // The owner is recorded and applies to handles opened without it
+            assert_eq!(chonk.meta().owner, Some(owner.clone()));
+            let plain = Chonk::builder(&env, symbol_short!("test"))
+                .temporary()
+                .build();
+            assert_eq!(plain.meta().owner, Some(owner.clone()));
+        });
+        assert_eq!(env.auths()[0].0, owner);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Auth, InvalidAction)")]
+    fn test_owner_requires_auth() {
+        use soroban_sdk::testutils::Address as _;
+
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+        let owner = soroban_sdk::Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .owner(owner)
+                .build();
+            chonk.push(Bytes::from_slice(&env, b"A"));
+        });
+    }
+
+    #[test]
+    fn test_owner_not_added_to_existing_collection() {
+        use soroban_sdk::testutils::Address as _;
+
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+        let owner = soroban_sdk::Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            Chonk::open(&env, symbol_short!("test")).push(Bytes::from_slice(&env, b"A"));
+
+            // Without mocked auths this would panic if the owner were applied
+            let claimer = Chonk::builder(&env, symbol_short!("test"))
+                .owner(owner)
+                .build();
+            claimer.push(Bytes::from_slice(&env, b"B"));
+            assert_eq!(claimer.meta().owner, None);
+        });
+        assert!(env.auths().is_empty());
+    }
+
+    #[test]
+    fn test_merge_sorted_into() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let a = Chonk::open(&env, symbol_short!("a"));
+            let b = Chonk::open(&env, symbol_short!("b"));
+            a.write_chunked(Bytes::from_slice(&env, b"ac"), 1);
+            b.write_chunked(Bytes::from_slice(&env, b"bd"), 1);
+
+            Chonk::merge_sorted_into(&a, &b, symbol_short!("merged"), |x, y| x.cmp(y));
+            let merged = Chonk::open(&env, symbol_short!("merged"));
+            assert_eq!(merged.count(), 4);
+            assert_eq!(merged.assemble(), Bytes::from_slice(&env, b"abcd"));
+            assert_eq!(merged.total_bytes(), 4);
+
+            // Merging into an input replaces it; the other input is kept
+            b.write_chunked(Bytes::from_slice(&env, b"a"), 1);
+            Chonk::merge_sorted_into(&a, &b, symbol_short!("b"), |x, y| x.cmp(y));
+            assert_eq!(b.assemble(), Bytes::from_slice(&env, b"aac"));
+            assert_eq!(a.assemble(), Bytes::from_slice(&env, b"ac"));
+        });
+    }
+
+    #[test]
+    fn test_merge_sorted_into_streams_to_dest() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let a = Chonk::open_linked(&env, symbol_short!("a"));
+            let b = Chonk::open(&env, symbol_short!("b"));
+            a.write_chunked(Bytes::from_slice(&env, b"bd"), 1);
+            b.write_chunked(Bytes::from_slice(&env, b"ace"), 1);
+
+            // The destination's old, longer content is replaced in one write
+            let dest = Chonk::open_linked(&env, symbol_short!("dest"));
+            dest.write_chunked(Bytes::from_slice(&env, b"zzzzzzz"), 1);
+            let version = dest.meta().version;
+
+            Chonk::merge_sorted_into(&a, &b, symbol_short!("dest"), |x, y| x.cmp(y));
+            assert_eq!(dest.assemble(), Bytes::from_slice(&env, b"abcde"));
+            assert_eq!(dest.count(), 5);
+            assert_eq!(dest.total_bytes(), 5);
+            assert_eq!(dest.meta().version, version + 1);
+            assert_eq!(dest.get(4), Some(Bytes::from_slice(&env, b"e")));
+        });
+    }
+
+    #[test]
+    fn test_next_index() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for _ in 0..3 {
+                let expected = chonk.next_index();
+                assert_eq!(chonk.push(Bytes::from_slice(&env, b"A")), expected);
+            }
+            assert_eq!(chonk.next_index(), 3);
+        });
+    }
+
+    #[test]
+    fn test_write_at() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"AAAABBBBCCCC"), 4);
+
+            // Spans the end of chunk 0 and the start of chunk 1
+            assert_eq!(chonk.write_at(2, Bytes::from_slice(&env, b"xyzw")), Ok(()));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AAxyzwBBCCCC"));
+            assert_eq!(chonk.get(2), Some(Bytes::from_slice(&env, b"CCCC")));
+            assert_eq!(chonk.meta().version, 2);
+
+            // Running past the end extends the content
+            assert_eq!(chonk.write_at(10, Bytes::from_slice(&env, b"1234")), Ok(()));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AAxyzwBBCC1234"));
+            assert_eq!(chonk.count(), 4);
+            assert_eq!(chonk.total_bytes(), 14);
+            assert_eq!(chonk.meta().version, 3);
+
+            assert_eq!(
+                chonk.write_at(15, Bytes::from_slice(&env, b"!")),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+            assert_eq!(chonk.write_at(14, Bytes::from_slice(&env, b"!")), Ok(()));
+            assert_eq!(chonk.total_bytes(), 15);
+        });
+    }
+
+    #[test]
+    fn test_write_at_limits() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::builder(&env, symbol_short!("test"))
+                .max_chunk_size(4)
+                .max_total_bytes(8)
+                .build();
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcd"), 4);
+            let version = chonk.meta().version;
+
+            // The bytes past the end would make a chunk longer than 4
+            assert_eq!(
+                chonk.write_at(2, Bytes::from_slice(&env, b"XYZZZZZ")),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            // Or push the content past 8 bytes
+            let capped = Chonk::builder(&env, symbol_short!("test"))
+                .max_total_bytes(8)
+                .build();
+            assert_eq!(
+                capped.write_at(4, Bytes::from_slice(&env, b"efghi")),
+                Err(ChonkError::StorageLimitExceeded)
+            );
+
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abcd"));
+            assert_eq!(chonk.meta().version, version);
+            assert_eq!(chonk.write_at(2, Bytes::from_slice(&env, b"XYZ")), Ok(()));
+            assert_eq!(chonk.lengths().collect::<std::vec::Vec<_>>(), [4, 1]);
+        });
+    }
+
+    #[test]
+    fn test_set_range() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"AAAABBBBCCCC"), 4);
+
+            // Four bytes spanning chunks 1 and 2
+            assert_eq!(chonk.set_range(6, Bytes::from_slice(&env, b"wxyz")), Ok(()));
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AAAABBwxyzCC"));
+            assert_eq!(chonk.total_bytes(), 12);
+            assert_eq!(chonk.lengths().collect::<std::vec::Vec<_>>(), [4, 4, 4]);
+
+            assert_eq!(
+                chonk.set_range(10, Bytes::from_slice(&env, b"!!!")),
+                Err(ChonkError::IndexOutOfBounds)
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"AAAABBwxyzCC"));
+        });
+    }
+
+    #[test]
+    fn test_fill_range() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefgh"), 4);
+
+            // Three bytes straddling chunks 0 and 1
+            chonk.fill_range(3, 0, 3);
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"abc\0")));
+            assert_eq!(chonk.get(1), Some(Bytes::from_slice(&env, b"\0\0gh")));
+
+            // Clamped to the end; past it, nothing is written
+            let version = chonk.meta().version;
+            chonk.fill_range(6, b'z', 10);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abc\0\0\0zz"));
+            chonk.fill_range(8, b'z', 1);
+            assert_eq!(chonk.meta().version, version + 1);
+            assert_eq!(chonk.total_bytes(), 8);
+        });
+    }
+
+    #[test]
+    fn test_rle_codec_round_trip() {
+        let env = Env::default();
+        let mut long_run = [7u8; 300];
+        long_run[299] = 8;
+
+        for data in [&b""[..], b"a", b"aaabccccd", &long_run] {
+            let data = Bytes::from_slice(&env, data);
+            assert_eq!(RleCodec.decode(&RleCodec.encode(&data)), data);
+        }
+        assert_eq!(
+            RleCodec.encode(&Bytes::from_slice(&env, b"aaab")),
+            Bytes::from_slice(&env, &[3, b'a', 1, b'b'])
+        );
+        // Runs are capped at 255
+        assert_eq!(
+            RleCodec.encode(&Bytes::from_slice(&env, &long_run)),
+            Bytes::from_slice(&env, &[255, 7, 44, 7, 1, 8])
+        );
+    }
+
+    #[test]
+    fn test_open_with_codec() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open_with_codec(&env, symbol_short!("test"), &RleCodec);
+            chonk.push(Bytes::from_slice(&env, &[0; 100]));
+            chonk.push(Bytes::from_slice(&env, b"xxyy"));
+            chonk.insert(1, Bytes::from_slice(&env, b"zzz"));
+            chonk.set(2, Bytes::from_slice(&env, b"yyyy"));
+
+            // Reads return the decoded bytes, and lengths describe them
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, &[0; 100])));
+            assert_eq!(chonk.get(1), Some(Bytes::from_slice(&env, b"zzz")));
+            let mut expected = Bytes::from_slice(&env, &[0; 100]);
+            expected.extend_from_slice(b"zzzyyyy");
+            assert_eq!(chonk.assemble(), expected);
+            assert_eq!(chonk.total_bytes(), 107);
+
+            // Storage holds the encoded bytes
+            let key = ChonkKey::Chunk(symbol_short!("test"), 0);
+            let stored: Bytes = env.storage().persistent().get(&key).unwrap();
+            assert_eq!(stored, Bytes::from_slice(&env, &[100, 0]));
+            assert_eq!(
+                Chonk::open(&env, symbol_short!("test")).get(2),
+                Some(Bytes::from_slice(&env, &[4, b'y']))
+            );
+        });
+    }
+
+    #[test]
+    fn test_slice_view() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefghij"), 3);
+
+            // Bytes 2..7 span three chunks
+            let slice = chonk.slice_view(2, 5);
+            assert_eq!(slice.len(), 5);
+            assert_eq!(slice.assemble(), Bytes::from_slice(&env, b"cdefg"));
+            assert_eq!(slice.read_bytes(1, 3), Bytes::from_slice(&env, b"def"));
+            assert_eq!(slice.read_bytes(4, 10), Bytes::from_slice(&env, b"g"));
+
+            // Clamped to the end of the content
+            let tail = chonk.slice_view(8, 5);
+            assert_eq!(tail.len(), 2);
+            assert_eq!(tail.assemble(), Bytes::from_slice(&env, b"ij"));
+            assert!(chonk.slice_view(10, 5).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_manifest() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert!(chonk.manifest().is_empty());
+
+            chonk.push(Bytes::from_slice(&env, b"abc"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"defghij"));
+
+            let manifest = chonk.manifest();
+            assert_eq!(manifest, soroban_sdk::Vec::from_array(&env, [3, 0, 7]));
+            assert_eq!(manifest.len(), chonk.count());
+        });
+    }
+
+    #[test]
+    fn test_chunk_range_bytes() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for chunk in [&b"a"[..], b"bb", b"ccc", b"dddd"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+
+            // Chunks 1 and 2
+            assert_eq!(chonk.chunk_range_bytes(1, 2), 5);
+            assert_eq!(chonk.chunk_range_bytes(0, 4), chonk.total_bytes());
+            // Clamped to the collection
+            assert_eq!(chonk.chunk_range_bytes(2, 10), 7);
+            assert_eq!(chonk.chunk_range_bytes(4, 1), 0);
+            assert_eq!(chonk.chunk_range_bytes(1, u32::MAX), 9);
+        });
+    }
+
+    #[test]
+    fn test_get_range_assembled() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for chunk in [&b"ab"[..], b"cd", b"ef", b"gh"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+
+            assert_eq!(
+                chonk.get_range_assembled(1, 2),
+                (Bytes::from_slice(&env, b"cdef"), 2)
+            );
+            // Clamped to the collection
+            assert_eq!(
+                chonk.get_range_assembled(2, 5),
+                (Bytes::from_slice(&env, b"efgh"), 2)
+            );
+            assert_eq!(chonk.get_range_assembled(4, 1), (Bytes::new(&env), 0));
+        });
+    }
+
+    #[test]
+    fn test_iter_indexed() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"a"));
+            chonk.push(Bytes::from_slice(&env, b"b"));
+            chonk.push(Bytes::from_slice(&env, b"c"));
+
+            let pairs: std::vec::Vec<(u32, Bytes)> = chonk.iter_indexed().collect();
+            assert_eq!(pairs.len() as u32, chonk.count());
+            for (expected, (index, chunk)) in pairs.into_iter().enumerate() {
+                assert_eq!(index, expected as u32);
+                assert_eq!(Some(chunk), chonk.get(index));
+            }
+        });
+    }
+
+    #[test]
+    fn test_iter_rev() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.iter_rev().count(), 0);
+            chonk.write_chunked(Bytes::from_slice(&env, b"aabbc"), 2);
+
+            let chunks: std::vec::Vec<Bytes> = chonk.iter_rev().collect();
+            assert_eq!(
+                chunks,
+                [
+                    Bytes::from_slice(&env, b"c"),
+                    Bytes::from_slice(&env, b"bb"),
+                    Bytes::from_slice(&env, b"aa"),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_chunk_size_limit_on_set_and_insert() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test")).with_max_chunk_size(4);
+            chonk.push(Bytes::from_slice(&env, b"abcd"));
+
+            let too_large = Bytes::from_slice(&env, b"abcde");
+            assert_eq!(
+                chonk.try_push(too_large.clone()),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(
+                chonk.try_set(0, too_large.clone()),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(
+                chonk.try_insert(0, too_large),
+                Err(ChonkError::ChunkTooLarge)
+            );
+
+            // Nothing was written
+            assert_eq!(chonk.count(), 1);
+            assert_eq!(chonk.get(0), Some(Bytes::from_slice(&env, b"abcd")));
+            assert_eq!(chonk.meta().version, 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
+    fn test_set_over_chunk_size_limit_panics() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test")).with_max_chunk_size(4);
+            chonk.push(Bytes::from_slice(&env, b"abcd"));
+            chonk.set(0, Bytes::from_slice(&env, b"abcde"));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
+    fn test_write_chunked_over_chunk_size_limit_panics() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test")).with_max_chunk_size(4);
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefgh"), 8);
+        });
+    }
+
+    #[test]
+    fn test_chunk_size_limit_on_stored_length() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test")).with_max_chunk_size(4);
+            chonk.push(Bytes::from_slice(&env, b"keep"));
+            assert_eq!(
+                chonk.write_slice_chunked(b"abcdefgh", 8),
+                Err(ChonkError::ChunkTooLarge)
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"keep"));
+
+            // With a codec the encoded length counts: a run of 8 encodes to
+            // 2 bytes, while 3 distinct bytes encode to 6
+            let rle = Chonk::open_with_codec(&env, symbol_short!("rle"), &RleCodec)
+                .with_max_chunk_size(4);
+            assert_eq!(rle.write_slice_chunked(b"aaaaaaaa", 8), Ok(1));
+            assert_eq!(
+                rle.try_push(Bytes::from_slice(&env, b"abc")),
+                Err(ChonkError::ChunkTooLarge)
+            );
+        });
+    }
+
+    #[test]
+    fn test_stats() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.stats(), (0, 0));
+
+            chonk.push(Bytes::from_slice(&env, b"abc"));
+            chonk.push(Bytes::from_slice(&env, b"defgh"));
+            assert_eq!(chonk.stats(), (chonk.count(), chonk.total_bytes()));
+            assert_eq!(chonk.stats(), (2, 8));
+        });
+    }
+
+    #[test]
+    fn test_overhead_bytes() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            let content = Bytes::from_slice(&env, &[7u8; 64]);
+
+            chonk.write_chunked(content.clone(), 32);
+            let two_chunks = chonk.overhead_bytes();
+            chonk.write_chunked(content, 8);
+            let eight_chunks = chonk.overhead_bytes();
+
+            // Same payload, six more entries
+            assert_eq!(eight_chunks - two_chunks, 6 * ENTRY_OVERHEAD_BYTES);
+            assert!(Chonk::open(&env, symbol_short!("empty")).overhead_bytes() > 0);
+        });
+    }
+
+    #[test]
+    fn test_count_matching() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.count_matching(|_| true), 0);
+
+            chonk.push(Bytes::from_slice(&env, b"a"));
+            chonk.push(Bytes::from_slice(&env, b"bc"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"def"));
+
+            assert_eq!(chonk.count_matching(|chunk| chunk.len() > 1), 2);
+            assert_eq!(chonk.count_matching(|chunk| chunk.is_empty()), 1);
+        });
+    }
+
+    #[test]
+    fn test_clone_range_into() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("src"));
+            for chunk in [b"a", b"b", b"c", b"d", b"e"] {
+                chonk.push(Bytes::from_slice(&env, chunk));
+            }
+
+            assert_eq!(chonk.clone_range_into(symbol_short!("dest"), 2, 2), 2);
+            let dest = Chonk::open(&env, symbol_short!("dest"));
+            assert_eq!(dest.assemble(), Bytes::from_slice(&env, b"cd"));
+            assert_eq!(dest.meta().count, 2);
+            assert_eq!(dest.meta().total_bytes, 2);
+            assert_eq!(dest.meta().version, 0);
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"abcde"));
+
+            // The range is clamped to the source's chunks
+            assert_eq!(chonk.clone_range_into(symbol_short!("tail"), 3, 10), 2);
+            let tail = Chonk::open(&env, symbol_short!("tail"));
+            assert_eq!(tail.assemble(), Bytes::from_slice(&env, b"de"));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Destination must be empty")]
+    fn test_clone_range_into_non_empty_panics() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("src"));
+            chonk.push(Bytes::from_slice(&env, b"a"));
+            let dest = Chonk::open(&env, symbol_short!("dest"));
+            dest.push(Bytes::from_slice(&env, b"b"));
+            chonk.clone_range_into(symbol_short!("dest"), 0, 1);
+        });
+    }
+
+    #[test]
+    fn test_assemble_up_to() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefghij"), 3);
+
+            let preview = chonk.assemble_up_to(5);
+            assert_eq!(preview.len(), 5);
+            assert_eq!(preview, chonk.assemble().slice(..5));
+
+            // A limit on a chunk boundary, zero, and one past the content
+            assert_eq!(chonk.assemble_up_to(6), Bytes::from_slice(&env, b"abcdef"));
+            assert!(chonk.assemble_up_to(0).is_empty());
+            assert_eq!(chonk.assemble_up_to(11), chonk.assemble());
+        });
+    }
+
+    #[test]
+    fn test_head_and_tail() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert!(chonk.tail(3).is_empty());
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefghij"), 4);
+
+            // Each spans two chunks
+            assert_eq!(chonk.head(6), Bytes::from_slice(&env, b"abcdef"));
+            assert_eq!(chonk.tail(5), Bytes::from_slice(&env, b"fghij"));
+
+            // On a chunk boundary, zero, and clamped to the content
+            assert_eq!(chonk.tail(2), Bytes::from_slice(&env, b"ij"));
+            assert!(chonk.tail(0).is_empty());
+            assert_eq!(chonk.tail(50), chonk.assemble());
+            assert_eq!(chonk.head(50), chonk.assemble());
+        });
+    }
+
+    #[test]
+    fn test_append_collection() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let a = Chonk::open(&env, symbol_short!("a"));
+            a.push(Bytes::from_slice(&env, b"one"));
+            let b = Chonk::open(&env, symbol_short!("b"));
+            b.push(Bytes::from_slice(&env, b"two"));
+            b.push(Bytes::from_slice(&env, b"three"));
+
+            a.append_collection(&b);
+
+            assert_eq!(a.count(), 3);
+            assert_eq!(a.total_bytes(), 11);
+            assert_eq!(a.assemble(), Bytes::from_slice(&env, b"onetwothree"));
+            assert_eq!(a.meta().version, 2);
+            assert!(b.is_empty());
+            assert_eq!(b.total_bytes(), 0);
+            assert!(!b.exists());
+        });
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let a = Chonk::builder(&env, symbol_short!("a"))
+                .hashed(true)
+                .build();
+            let b = Chonk::builder(&env, symbol_short!("b"))
+                .hashed(true)
+                .build();
+            let plain = Chonk::open(&env, symbol_short!("plain"));
+            for chonk in [&a, &b, &plain] {
+                chonk.push(Bytes::from_slice(&env, b"hello"));
+                chonk.push(Bytes::from_slice(&env, b"world"));
+            }
+
+            // Kept in metadata by hashed collections, computed otherwise
+            let digest = a.meta().content_hash.unwrap();
+            assert_eq!(a.meta().hash_version, 2);
+            assert_eq!(a.content_hash(), digest);
+            assert!(plain.meta().content_hash.is_none());
+            assert_eq!(plain.content_hash(), digest);
+            assert!(a.content_eq_hash(&b));
+            assert!(a.content_eq_hash(&plain));
+
+            // Updated on every write, by handles opened without the flag too
+            Chonk::open(&env, symbol_short!("b")).set(1, Bytes::from_slice(&env, b"there"));
+            assert!(!a.content_eq_hash(&b));
+            assert_eq!(b.meta().hash_version, 3);
+            plain.set(1, Bytes::from_slice(&env, b"there"));
+            assert!(b.content_eq_hash(&plain));
+        });
+    }
+
+    #[test]
+    fn test_content_hash_folds_pushes() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let hashed = Chonk::builder(&env, symbol_short!("hashed"))
+                .hashed(true)
+                .build();
+            let plain = Chonk::open(&env, symbol_short!("plain"));
+
+            for chunk in [&b"alpha"[..], b"", b"beta", b"gamma"] {
+                hashed.push(Bytes::from_slice(&env, chunk));
+                plain.push(Bytes::from_slice(&env, chunk));
+
+                // The running digest always matches a full recompute
+                let meta = hashed.meta();
+                assert_eq!(meta.hash_version, meta.version);
+                assert_eq!(meta.content_hash.unwrap(), plain.content_hash());
+            }
+
+            // A middle edit recomputes, and pushes keep folding afterwards
+            hashed.set(1, Bytes::from_slice(&env, b"x"));
+            plain.set(1, Bytes::from_slice(&env, b"x"));
+            hashed.push(Bytes::from_slice(&env, b"delta"));
+            plain.push(Bytes::from_slice(&env, b"delta"));
+            assert_eq!(hashed.meta().content_hash.unwrap(), plain.content_hash());
+        });
+    }
+
+    #[test]
+    fn test_checksum_per_chunk() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"123456789"));
+            chonk.push(Bytes::new(&env));
+            chonk.push(Bytes::from_slice(&env, b"abc"));
+
+            let before = chonk.checksum_per_chunk();
+            assert_eq!(before.len(), 3);
+            // Standard CRC-32 check values
+            assert_eq!(before.get(0), Some(0xCBF4_3926));
+            assert_eq!(before.get(1), Some(0));
+
+            chonk.set(2, Bytes::from_slice(&env, b"abd"));
+            let after = chonk.checksum_per_chunk();
+            assert_eq!(after.get(0), before.get(0));
+            assert_eq!(after.get(1), before.get(1));
+            assert_ne!(after.get(2), before.get(2));
+        });
+    }
+
+    #[test]
+    fn test_verify_against() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push(Bytes::from_slice(&env, b"abc"));
+            chonk.push(Bytes::from_slice(&env, b"def"));
+
+            let checksums = chonk.checksum_per_chunk();
+            assert_eq!(chonk.verify_against(checksums.clone()), Ok(()));
+
+            let mut wrong = checksums.clone();
+            wrong.set(1, checksums.get(1).unwrap() ^ 1);
+            assert_eq!(
+                chonk.verify_against(wrong),
+                Err(ChonkError::IntegrityMismatch)
+            );
+
+            let mut short = checksums;
+            short.pop_back();
+            assert_eq!(
+                chonk.verify_against(short),
+                Err(ChonkError::IntegrityMismatch)
+            );
+        });
+    }
+
+    #[test]
+    fn test_assert_consistent() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            assert_eq!(chonk.assert_consistent(), Ok(()));
+            chonk.write_chunked(Bytes::from_slice(&env, b"consistent"), 4);
+            assert_eq!(chonk.assert_consistent(), Ok(()));
+
+            // Desync total_bytes behind the crate's back
+            let key = meta_key(&env, symbol_short!("test"));
+            let mut meta = chonk.meta();
+            meta.total_bytes += 1;
+            env.storage().persistent().set(&key, &meta);
+            assert_eq!(chonk.assert_consistent(), Err(ChonkError::Corrupted));
+
+            // A stray chunk past the end
+            meta.total_bytes -= 1;
+            env.storage().persistent().set(&key, &meta);
+            assert_eq!(chonk.assert_consistent(), Ok(()));
+            let stray = ChonkKey::Chunk(symbol_short!("test"), 3);
+            env.storage().persistent().set(&stray, &Bytes::new(&env));
+            assert_eq!(chonk.assert_consistent(), Err(ChonkError::Corrupted));
+        });
+    }
+
+    #[test]
+    fn test_corrupted_index() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"abcdefghi"), 3);
+            assert_eq!(chonk.corrupted_index(), None);
+
+            // Lose the middle chunk
+            let key = ChonkKey::Chunk(symbol_short!("test"), 1);
+            env.storage().persistent().remove(&key);
+            assert_eq!(chonk.assert_consistent(), Err(ChonkError::Corrupted));
+            assert_eq!(chonk.corrupted_index(), Some(1));
+        });
+    }
+
+    #[test]
+    fn test_open_with_each_storage_kind() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let content = Bytes::from_slice(&env, b"same content everywhere");
+            for kind in [
+                StorageKind::Persistent,
+                StorageKind::Temporary,
+                StorageKind::Instance,
+            ] {
+                let chonk = Chonk::open_with(&env, symbol_short!("test"), kind);
+                chonk.write_chunked(content.clone(), 5);
+                chonk.set(0, Bytes::from_slice(&env, b"SAME "));
+                assert_eq!(chonk.count(), 5);
+                assert_eq!(
+                    chonk.assemble(),
+                    Bytes::from_slice(&env, b"SAME content everywhere")
+                );
+            }
+
+            // Each kind holds its own copy under the same keys
+            let key = ChonkKey::Chunk(symbol_short!("test"), 0);
+            assert!(env.storage().persistent().has(&key));
+            assert!(env.storage().temporary().has(&key));
+            assert!(env.storage().instance().has(&key));
+        });
+    }
+
+    #[test]
+    fn test_migrate_storage() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let staged = Chonk::builder(&env, symbol_short!("test"))
+                .temporary()
+                .journaled(true)
+                .build();
+            staged.write_chunked(Bytes::from_slice(&env, b"promote me"), 4);
+            let meta = staged.meta();
+
+            let promoted = staged.migrate_storage(StorageKind::Persistent);
+            assert_eq!(promoted.meta(), meta);
+            assert_eq!(promoted.assemble(), Bytes::from_slice(&env, b"promote me"));
+            assert_eq!(promoted.changes_since(0).len(), 1);
+
+            let key = ChonkKey::Chunk(symbol_short!("test"), 0);
+            assert!(env.storage().persistent().has(&key));
+            assert!(!env.storage().temporary().has(&key));
+            assert!(
+                !env.storage()
+                    .temporary()
+                    .has(&meta_key(&env, symbol_short!("test")))
+            );
+            assert!(staged.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_migrate_storage_invalidates_cache() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let cache = ChonkCache::new();
+            let chonk = Chonk::open_cached(&env, symbol_short!("test"), &cache);
+            chonk.write_chunked(Bytes::from_slice(&env, b"move me"), 4);
+
+            let moved = chonk.migrate_storage(StorageKind::Temporary);
+
+            // The old location reads as empty, not as the cached metadata
+            assert_eq!(chonk.meta().count, 0);
+            assert_eq!(chonk.meta().version, 0);
+            assert_eq!(moved.count(), 2);
+        });
+    }
+
+    #[test]
+    fn test_try_open() {
+        use soroban_sdk::Symbol;
+
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            assert!(matches!(
+                Chonk::try_open(&env, Symbol::new(&env, "")),
+                Err(ChonkError::InvalidArgument)
+            ));
+
+            let chonk = Chonk::try_open(&env, Symbol::new(&env, "valid_id")).unwrap();
+            chonk.push(Bytes::from_slice(&env, b"data"));
+            assert_eq!(chonk.count(), 1);
+        });
+    }
+
+    #[test]
+    fn test_extend_from_slices() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.push_slice(b"first");
+
+            chonk.extend_from_slices(&[b"one", b"two", b"three"]);
+            assert_eq!(chonk.count(), 4);
+            assert_eq!(chonk.total_bytes(), 16);
+            assert_eq!(chonk.get(3), Some(Bytes::from_slice(&env, b"three")));
+            assert_eq!(chonk.meta().version, 2);
+
+            // Nothing to add leaves the collection untouched
+            chonk.extend_from_slices(&[]);
+            assert_eq!(chonk.meta().version, 2);
+        });
+    }
+
+    #[test]
+    fn test_error_discriminants_are_stable() {
+        let errors = [
+            (ChonkError::IndexOutOfBounds, 1),
+            (ChonkError::NotFound, 2),
+            (ChonkError::ChunkTooLarge, 3),
+            (ChonkError::StorageLimitExceeded, 4),
+            (ChonkError::AppendOnly, 5),
+            (ChonkError::VersionConflict, 6),
+            (ChonkError::InvalidFormat, 7),
+            (ChonkError::InvalidChunkSize, 8),
+            (ChonkError::IntegrityMismatch, 9),
+            (ChonkError::InvalidArgument, 10),
+            (ChonkError::Corrupted, 11),
+        ];
+        for (error, code) in errors {
+            assert_eq!(error as u32, code);
+            assert_eq!(
+                soroban_sdk::Error::from(error),
+                soroban_sdk::Error::from_contract_error(code)
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "base64"))]
+mod base64_tests {
+    use super::*;
+    use crate::test_support::test_contract_id;
+    use soroban_sdk::{Bytes, Env, symbol_short};
+
+    #[test]
+    fn test_base64_round_trip() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            for (plain, encoded) in [
+                (&b""[..], &b""[..]),
+                (b"f", b"Zg=="),
+                (b"fo", b"Zm8="),
+                (b"foo", b"Zm9v"),
+                (b"foobar", b"Zm9vYmFy"),
+            ] {
+                chonk.write_chunked(Bytes::from_slice(&env, plain), 4);
+                assert_eq!(chonk.assemble_base64(), Bytes::from_slice(&env, encoded));
+            }
+
+            // Encoding streams across chunk boundaries
+            let content = Bytes::from_slice(&env, b"chunked content, any \x00\xff bytes");
+            chonk.write_chunked(content.clone(), 5);
+            let encoded = chonk.assemble_base64();
+
+            let copy = Chonk::open(&env, symbol_short!("copy"));
+            assert_eq!(copy.import_base64(encoded, 8), Ok(()));
+            assert_eq!(copy.assemble(), content);
+            assert_eq!(copy.get(0).unwrap().len(), 8);
+        });
+    }
+
+    #[test]
+    fn test_base64_invalid_input() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"keep"), 4);
+
+            for bad in [&b"Zm9"[..], b"Zm9v!A==", b"Zg==Zm9v", b"Z===", b"Zm=v"] {
+                assert_eq!(
+                    chonk.import_base64(Bytes::from_slice(&env, bad), 4),
+                    Err(ChonkError::InvalidFormat)
+                );
+            }
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"keep"));
+        });
+    }
+
+    #[test]
+    fn test_base64_zero_chunk_size() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"keep"), 4);
+
+            assert_eq!(
+                chonk.import_base64(Bytes::from_slice(&env, b"Zm9v"), 0),
+                Err(ChonkError::InvalidChunkSize)
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"keep"));
+        });
+    }
+}
+
+#[cfg(all(test, feature = "hex"))]
+mod hex_tests {
+    use super::*;
+    use crate::test_support::test_contract_id;
+    use soroban_sdk::{Bytes, Env, symbol_short};
+
+    #[test]
+    fn test_hex_round_trip() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_array(&env, &[0x00, 0x1f, 0xa0, 0xff, 0x42]), 2);
+            let hex = chonk.assemble_hex();
+            assert_eq!(hex, Bytes::from_slice(&env, b"001fa0ff42"));
+
+            let copy = Chonk::open(&env, symbol_short!("copy"));
+            assert_eq!(copy.import_hex(hex, 3), Ok(()));
+            assert_eq!(copy.assemble(), chonk.assemble());
+            assert_eq!(copy.count(), 2);
+
+            // Uppercase digits decode too
+            assert_eq!(copy.import_hex(Bytes::from_slice(&env, b"BEEF"), 3), Ok(()));
+            assert_eq!(copy.assemble(), Bytes::from_array(&env, &[0xbe, 0xef]));
+        });
+    }
+
+    #[test]
+    fn test_hex_invalid_input() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"keep"), 4);
+
+            for bad in [&b"abc"[..], b"zz", b"0x12", b"12 4"] {
+                assert_eq!(
+                    chonk.import_hex(Bytes::from_slice(&env, bad), 4),
+                    Err(ChonkError::InvalidFormat)
+                );
+            }
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"keep"));
+        });
+    }
+
+    #[test]
+    fn test_hex_zero_chunk_size() {
+        let env = Env::default();
+        let contract_id = test_contract_id(&env);
+
+        env.as_contract(&contract_id, || {
+            let chonk = Chonk::open(&env, symbol_short!("test"));
+            chonk.write_chunked(Bytes::from_slice(&env, b"keep"), 4);
+
+            assert_eq!(
+                chonk.import_hex(Bytes::from_slice(&env, b"beef"), 0),
+                Err(ChonkError::InvalidChunkSize)
+            );
+            assert_eq!(chonk.assemble(), Bytes::from_slice(&env, b"keep"));
+        });
+    }
 }
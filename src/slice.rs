@@ -0,0 +1,70 @@
+use crate::chonk::Chonk;
+use soroban_sdk::Bytes;
+
+/// Lazy view of a range of a collection's logical bytes, created with
+/// [`Chonk::slice_view`]
+///
+/// Holds only the range; chunks are read when content is requested, and
+/// the range is clamped to the content as it is at that point.
+#[derive(Clone, Copy)]
+pub struct ChonkSlice<'a> {
+    chonk: &'a Chonk<'a>,
+    start: u32,
+    len: u32,
+}
+
+impl<'a> ChonkSlice<'a> {
+    pub(crate) fn new(chonk: &'a Chonk<'a>, start: u32, len: u32) -> Self {
+        Self { chonk, start, len }
+    }
+
+    /// Offset of the slice's first byte in the collection
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    /// Number of bytes the slice covers, up to the end of the content
+    pub fn len(&self) -> u32 {
+        let total = self.chonk.total_bytes();
+        let available = total.saturating_sub(self.start as u64);
+        core::cmp::min(self.len as u64, available) as u32
+    }
+
+    /// Check if the slice covers no bytes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read `len` bytes starting `offset` bytes into the slice, clamped to
+    /// the slice's end
+    pub fn read_bytes(&self, offset: u32, len: u32) -> Bytes {
+        let mut result = Bytes::new(self.chonk.env);
+        let offset = core::cmp::min(offset, self.len);
+        let start = self.start as u64 + offset as u64;
+        let end = start + core::cmp::min(len, self.len - offset) as u64;
+        if start == end {
+            return result;
+        }
+
+        let mut pos = 0u64;
+        for chunk in self.chonk.iter() {
+            let (chunk_start, chunk_end) = (pos, pos + chunk.len() as u64);
+            pos = chunk_end;
+            if chunk_end <= start {
+                continue;
+            }
+            if chunk_start >= end {
+                break;
+            }
+            let from = (core::cmp::max(start, chunk_start) - chunk_start) as u32;
+            let to = (core::cmp::min(end, chunk_end) - chunk_start) as u32;
+            result.append(&chunk.slice(from..to));
+        }
+        result
+    }
+
+    /// Assemble the slice's bytes into a single Bytes
+    pub fn assemble(&self) -> Bytes {
+        self.read_bytes(0, self.len)
+    }
+}
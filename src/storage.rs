@@ -0,0 +1,98 @@
+use crate::types::ChonkKey;
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+/// Which contract storage a collection's entries live in
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StorageKind {
+    /// Persistent storage; entries survive archival and can be restored
+    #[default]
+    Persistent,
+    /// Temporary storage; entries are deleted once their TTL expires
+    Temporary,
+    /// Instance storage; entries share the contract instance's TTL and
+    /// size limit
+    Instance,
+}
+
+/// Contract storage of one kind, with the operations a collection needs
+#[derive(Clone, Copy)]
+pub(crate) struct Store<'a> {
+    pub(crate) env: &'a Env,
+    kind: StorageKind,
+    /// `(threshold, extend_to)` applied to every entry written
+    ttl: Option<(u32, u32)>,
+}
+
+impl<'a> Store<'a> {
+    pub(crate) fn new(env: &'a Env, kind: StorageKind) -> Self {
+        Self {
+            env,
+            kind,
+            ttl: None,
+        }
+    }
+
+    /// Extend the TTL of each entry written through this store
+    pub(crate) fn with_ttl(mut self, ttl: Option<(u32, u32)>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub(crate) fn has(&self, key: &ChonkKey) -> bool {
+        match self.kind {
+            StorageKind::Persistent => self.env.storage().persistent().has(key),
+            StorageKind::Temporary => self.env.storage().temporary().has(key),
+            StorageKind::Instance => self.env.storage().instance().has(key),
+        }
+    }
+
+    pub(crate) fn get<V: TryFromVal<Env, Val>>(&self, key: &ChonkKey) -> Option<V> {
+        match self.kind {
+            StorageKind::Persistent => self.env.storage().persistent().get(key),
+            StorageKind::Temporary => self.env.storage().temporary().get(key),
+            StorageKind::Instance => self.env.storage().instance().get(key),
+        }
+    }
+
+    pub(crate) fn set<V: IntoVal<Env, Val>>(&self, key: &ChonkKey, value: &V) {
+        match self.kind {
+            StorageKind::Persistent => self.env.storage().persistent().set(key, value),
+            StorageKind::Temporary => self.env.storage().temporary().set(key, value),
+            StorageKind::Instance => self.env.storage().instance().set(key, value),
+        }
+        if let Some((threshold, extend_to)) = self.ttl {
+            self.extend_ttl(key, threshold, extend_to);
+        }
+    }
+
+    /// Extend the entry's TTL to `extend_to` ledgers if it is below
+    /// `threshold`. Instance entries share the instance's TTL, so that is
+    /// extended instead.
+    pub(crate) fn extend_ttl(&self, key: &ChonkKey, threshold: u32, extend_to: u32) {
+        match self.kind {
+            StorageKind::Persistent => self
+                .env
+                .storage()
+                .persistent()
+                .extend_ttl(key, threshold, extend_to),
+            StorageKind::Temporary => self
+                .env
+                .storage()
+                .temporary()
+                .extend_ttl(key, threshold, extend_to),
+            StorageKind::Instance => self
+                .env
+                .storage()
+                .instance()
+                .extend_ttl(threshold, extend_to),
+        }
+    }
+
+    pub(crate) fn remove(&self, key: &ChonkKey) {
+        match self.kind {
+            StorageKind::Persistent => self.env.storage().persistent().remove(key),
+            StorageKind::Temporary => self.env.storage().temporary().remove(key),
+            StorageKind::Instance => self.env.storage().instance().remove(key),
+        }
+    }
+}
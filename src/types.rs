@@ -1,13 +1,44 @@
-use soroban_sdk::{Symbol, contracttype};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Symbol, Vec, contracttype};
 
 /// Storage keys for chunked content
-#[derive(Clone)]
+///
+/// The `contracttype` encoding of these keys is part of the crate's semver
+/// contract: off-chain tooling may build them with [`chunk_key`] and
+/// [`meta_key`] to read collection entries directly from the ledger.
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum ChonkKey {
     /// Metadata for a content collection: collection_id -> ChonkMeta
     Meta(Symbol),
     /// Individual chunk: (collection_id, index) -> Bytes
     Chunk(Symbol, u32),
+    /// Chunk count mirrored out of the metadata: collection_id -> u32
+    Count(Symbol),
+    /// Chunk of a gapped collection at a physical slot: (collection_id, slot) -> Bytes
+    Slot(Symbol, u32),
+    /// Node of a linked collection: (collection_id, node) -> ChonkNode
+    Node(Symbol, u32),
+    /// Journal entry of a journaled collection: (collection_id, version) ->
+    /// first index affected by the write that produced `version`
+    Change(Symbol, u64),
+}
+
+/// Storage key of the chunk at `index` in collection `id`.
+///
+/// Only valid for dense collections, whose chunks are stored under
+/// `ChonkKey::Chunk` by index. Gapped and linked collections keep them under
+/// `Slot` and `Node` keys that are not derived from the index alone, and a
+/// collection with a codec stores the encoded bytes.
+///
+/// `env` is unused today; it keeps the signature stable should key
+/// construction ever need it.
+pub fn chunk_key(_env: &Env, id: Symbol, index: u32) -> ChonkKey {
+    ChonkKey::Chunk(id, index)
+}
+
+/// Storage key of the metadata entry for collection `id`
+pub fn meta_key(_env: &Env, id: Symbol) -> ChonkKey {
+    ChonkKey::Meta(id)
 }
 
 /// Metadata about a chunked content collection
@@ -17,9 +48,82 @@ pub struct ChonkMeta {
     /// Number of chunks in this collection
     pub count: u32,
     /// Total size in bytes across all chunks
-    pub total_bytes: u32,
-    /// Version for optimistic locking (incremented on each write)
-    pub version: u32,
+    pub total_bytes: u64,
+    /// Version for optimistic locking (incremented on each write).
+    /// 64 bits wide so a long-lived, high-churn collection never wraps back
+    /// to a version a reader may already have seen.
+    pub version: u64,
+    /// How chunk indices map to storage entries
+    pub layout: ChonkLayout,
+    /// Existing chunks may not be changed or removed; new chunks may only
+    /// be added at the end
+    pub append_only: bool,
+    /// Mutations publish contract events
+    pub events: bool,
+    /// Mutations are recorded under `ChonkKey::Change`, keyed by version
+    pub journal: bool,
+    /// Chunk size `append` must be called with, if set
+    pub chunk_size: Option<u32>,
+    /// Informational content type (e.g. a MIME type) for consumers
+    pub content_type: Option<Bytes>,
+    /// Address whose authorization every write requires
+    pub owner: Option<Address>,
+    /// Writes keep `content_hash` current
+    pub hashed: bool,
+    /// Digest of the content kept by hashed collections, as of version
+    /// `hash_version`
+    pub content_hash: Option<BytesN<32>>,
+    /// Version `content_hash` was computed at
+    pub hash_version: u64,
+}
+
+/// How a collection maps logical chunk indices to storage entries
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum ChonkLayout {
+    /// Chunk `i` is stored under `ChonkKey::Chunk(id, i)`; inserts and
+    /// removes shift every later chunk
+    Dense,
+    /// Chunk `i` is stored under `ChonkKey::Slot(id, slots.map[i])`; inserts
+    /// and removes only touch the affected chunk and the map
+    Gapped(ChonkSlots),
+    /// Chunks are nodes of a doubly-linked list under `ChonkKey::Node`;
+    /// inserts and removes only touch the affected nodes and their
+    /// neighbours, but finding chunk `i` walks the list
+    Linked(ChonkLinks),
+}
+
+/// Logical-to-physical index map of a gapped collection
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ChonkSlots {
+    /// Physical slot of each chunk, in logical order
+    pub map: Vec<u32>,
+    /// Next never-used physical slot
+    pub next: u32,
+}
+
+/// Ends of a linked collection's node list
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ChonkLinks {
+    /// Node holding the first chunk
+    pub head: Option<u32>,
+    /// Node holding the last chunk
+    pub tail: Option<u32>,
+    /// Next never-used node id
+    pub next: u32,
+}
+
+/// One chunk of a linked collection
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ChonkNode {
+    pub data: Bytes,
+    /// Node holding the previous chunk
+    pub prev: Option<u32>,
+    /// Node holding the next chunk
+    pub next: Option<u32>,
 }
 
 impl ChonkMeta {
@@ -28,6 +132,16 @@ impl ChonkMeta {
             count: 0,
             total_bytes: 0,
             version: 0,
+            layout: ChonkLayout::Dense,
+            append_only: false,
+            events: false,
+            journal: false,
+            chunk_size: None,
+            content_type: None,
+            owner: None,
+            hashed: false,
+            content_hash: None,
+            hash_version: 0,
         }
     }
 }
@@ -37,3 +151,33 @@ impl Default for ChonkMeta {
         Self::new()
     }
 }
+
+/// Metadata layout written by 1.0 releases, kept so stored entries can be
+/// upgraded on read
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype(export = false)]
+pub(crate) struct ChonkMetaV1 {
+    pub count: u32,
+    pub total_bytes: u32,
+    pub version: u32,
+}
+
+impl From<ChonkMetaV1> for ChonkMeta {
+    fn from(legacy: ChonkMetaV1) -> Self {
+        Self {
+            count: legacy.count,
+            total_bytes: legacy.total_bytes as u64,
+            version: legacy.version as u64,
+            layout: ChonkLayout::Dense,
+            append_only: false,
+            events: false,
+            journal: false,
+            chunk_size: None,
+            content_type: None,
+            owner: None,
+            hashed: false,
+            content_hash: None,
+            hash_version: 0,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::chonk::Chonk;
+use crate::iter::ChonkIter;
+use crate::types::ChonkMeta;
+use soroban_sdk::{Bytes, Symbol, Vec};
+
+/// Read-only view of a collection, created with [`Chonk::view`]
+///
+/// Exposes only reads, so a contract method holding a `ChonkView` cannot
+/// write to the collection; attempting to is a compile error:
+///
+/// ```compile_fail
+/// # use soroban_chonk::Chonk;
+/// # use soroban_sdk::{Bytes, Env, symbol_short};
+/// # let env = Env::default();
+/// let view = Chonk::open(&env, symbol_short!("doc")).view();
+/// view.push(Bytes::new(&env));
+/// ```
+#[derive(Clone)]
+pub struct ChonkView<'a> {
+    chonk: Chonk<'a>,
+}
+
+impl<'a> ChonkView<'a> {
+    pub(crate) fn new(chonk: Chonk<'a>) -> Self {
+        Self { chonk }
+    }
+
+    /// Get the collection ID
+    pub fn id(&self) -> &Symbol {
+        self.chonk.id()
+    }
+
+    /// Get metadata for this collection
+    pub fn meta(&self) -> ChonkMeta {
+        self.chonk.meta()
+    }
+
+    /// Get number of chunks
+    pub fn count(&self) -> u32 {
+        self.chonk.count()
+    }
+
+    /// Get total bytes across all chunks
+    pub fn total_bytes(&self) -> u64 {
+        self.chonk.total_bytes()
+    }
+
+    /// Check if the collection is empty
+    pub fn is_empty(&self) -> bool {
+        self.chonk.is_empty()
+    }
+
+    /// Get a single chunk
+    pub fn get(&self, index: u32) -> Option<Bytes> {
+        self.chonk.get(index)
+    }
+
+    /// Get `count` chunks starting at `start`
+    pub fn get_range(&self, start: u32, count: u32) -> Vec<Bytes> {
+        self.chonk.get_range(start, count)
+    }
+
+    /// Iterate over all chunks
+    pub fn iter(&self) -> ChonkIter<'_> {
+        self.chonk.iter()
+    }
+
+    /// Assemble all chunks into a single Bytes
+    pub fn assemble(&self) -> Bytes {
+        self.chonk.assemble()
+    }
+}